@@ -0,0 +1,72 @@
+//! Benchmarks of the pricing hot paths over synthetic chains.
+//!
+//! Run with `cargo bench`. Measures `get_price` and `get_greeks` on chains of
+//! 10k, 100k and 1M options, throughput is reported per option. Filter by
+//! name as usual with criterion, e.g. `cargo bench -- get_price/10000`.
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use options::opt_data::OptData;
+use options::options_struct::{OptTypes, Options};
+use options::pricing_models::black_scholes::BlackScholesModel;
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+/// # chain
+/// Synthetic chain of `n` options spread across strikes and maturities.
+fn chain(n: usize) -> Options {
+    let settle = Utc.with_ymd_and_hms(2022, 9, 14, 15, 0, 0).unwrap();
+    Options::new(
+        OptData::new(
+            vec!["SYN".to_string(); n],
+            (0..n)
+                .map(|i| {
+                    if i % 2 == 0 {
+                        OptTypes::Call
+                    } else {
+                        OptTypes::Put
+                    }
+                })
+                .collect(),
+            vec![100.0; n],
+            (0..n).map(|i| 50.0 + (i % 101) as f64).collect(),
+            vec![settle; n],
+            (0..n)
+                .map(|i| settle + Duration::days(7 + (i % 720) as i64))
+                .collect(),
+            vec![0.01; n],
+            vec![0.03; n],
+            (0..n).map(|i| 0.1 + 0.005 * (i % 80) as f64).collect(),
+        ),
+        Box::new(BlackScholesModel::new()),
+    )
+}
+
+/// # pricing
+/// Prices and greeks per chain size, few samples since the largest chain
+/// takes a noticeable time per run.
+fn pricing(c: &mut Criterion) {
+    for (name, greeks) in [("get_price", false), ("get_greeks", true)] {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        for n in SIZES {
+            let mut opt = chain(n);
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+                b.iter(|| {
+                    if greeks {
+                        opt.get_greeks();
+                        black_box(&opt.greeks);
+                    } else {
+                        opt.get_prices();
+                        black_box(&opt.prices);
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, pricing);
+criterion_main!(benches);
@@ -0,0 +1,126 @@
+use crate::options_struct::{OptTypes, Options};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// # ArbitrageViolation
+/// A set of rows whose market quotes admit static arbitrage, see
+/// [`Options::arbitrage_violations`]. Rows are indices into the checked Options.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArbitrageViolation {
+    /// Total implied variance decreases from the shorter to the longer maturity
+    /// at the same ticker, option type and strike.
+    Calendar {
+        rows: (usize, usize),
+        /// Total variance `iv^2 * T` of the shorter and longer maturity.
+        total_variance: (f64, f64),
+    },
+    /// Market prices are not convex in strike at the same ticker, option type
+    /// and maturity, implying a negative density.
+    Butterfly {
+        rows: (usize, usize, usize),
+        /// Amount by which the middle price exceeds the interpolated wing prices.
+        excess: f64,
+    },
+}
+
+impl ArbitrageViolation {
+    /// # self.first_row
+    /// Lowest row index involved, used to order violations.
+    fn first_row(&self) -> usize {
+        match self {
+            ArbitrageViolation::Calendar { rows, .. } => rows.0.min(rows.1),
+            ArbitrageViolation::Butterfly { rows, .. } => rows.0.min(rows.1).min(rows.2),
+        }
+    }
+}
+
+impl Options {
+    /// # self.arbitrage_violations
+    /// Checks the market prices for calendar spread and butterfly arbitrage.
+    ///
+    /// Calendar arbitrage compares total implied variance across maturities
+    /// at a fixed strike, using [`Options::implied_vol`] when solved and
+    /// solving from [`crate::opt_data::OptData::market_price`] otherwise.
+    /// Butterfly arbitrage checks convexity of market prices in strike for
+    /// every three neighbouring strikes. Rows without a market price or
+    /// without an implied vol are ignored.
+    ///
+    /// # args:
+    /// * `tol` - Tolerance below which violations are not reported.
+    ///
+    /// # returns:
+    /// The violations ordered by their lowest row, empty if arbitrage free.
+    pub fn arbitrage_violations(&self, tol: f64) -> Vec<ArbitrageViolation> {
+        let data = &self.opt_data;
+        let n = data.tickers.len();
+        let implied_vol: Vec<f64> = if self.implied_vol.len() == n {
+            self.implied_vol.clone()
+        } else {
+            (0..n)
+                .map(|i| match data.market_price[i] {
+                    Some(market) => self.implied_vol_at(i, market),
+                    None => f64::NAN,
+                })
+                .collect()
+        };
+
+        let mut strikes: HashMap<(&str, OptTypes, u64), Vec<usize>> = HashMap::new();
+        let mut expiries: HashMap<(&str, OptTypes, DateTime<Utc>), Vec<usize>> = HashMap::new();
+        for (i, iv) in implied_vol.iter().enumerate() {
+            if data.market_price[i].is_none() {
+                continue;
+            }
+            let (ticker, opt_type) = (data.tickers[i].as_str(), data.opt_types[i]);
+            if !iv.is_nan() {
+                strikes
+                    .entry((ticker, opt_type, data.strike[i].to_bits()))
+                    .or_default()
+                    .push(i);
+            }
+            expiries
+                .entry((ticker, opt_type, data.maturities[i]))
+                .or_default()
+                .push(i);
+        }
+
+        let mut violations = Vec::new();
+        for mut rows in strikes.into_values() {
+            rows.sort_by(|&a, &b| data.duration[a].total_cmp(&data.duration[b]));
+            for pair in rows.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                let total_variance = (
+                    implied_vol[a].powi(2) * data.duration[a],
+                    implied_vol[b].powi(2) * data.duration[b],
+                );
+                if data.duration[a] < data.duration[b] && total_variance.1 < total_variance.0 - tol
+                {
+                    violations.push(ArbitrageViolation::Calendar {
+                        rows: (a, b),
+                        total_variance,
+                    });
+                }
+            }
+        }
+        for mut rows in expiries.into_values() {
+            rows.sort_by(|&a, &b| data.strike[a].total_cmp(&data.strike[b]));
+            for triple in rows.windows(3) {
+                let (a, b, c) = (triple[0], triple[1], triple[2]);
+                let (k1, k2, k3) = (data.strike[a], data.strike[b], data.strike[c]);
+                if k1 >= k2 || k2 >= k3 {
+                    continue;
+                }
+                let price = |i: usize| data.market_price[i].unwrap();
+                let w = (k3 - k2) / (k3 - k1);
+                let excess = price(b) - (w * price(a) + (1.0 - w) * price(c));
+                if excess > tol {
+                    violations.push(ArbitrageViolation::Butterfly {
+                        rows: (a, b, c),
+                        excess,
+                    });
+                }
+            }
+        }
+        violations.sort_by_key(|v| v.first_row());
+        violations
+    }
+}
@@ -0,0 +1,133 @@
+use crate::opt_data::{year_fraction, DAYS_PER_YEAR};
+use crate::options_struct::{LengthError, Options};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc, Weekday};
+use std::collections::BTreeSet;
+
+/// # DayCount
+/// Year fraction convention between two instants, e.g. the basis a rate
+/// curve is quoted on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DayCount {
+    /// Elapsed time over [`DAYS_PER_YEAR`] days, the basis of
+    /// [`OptData::duration`](crate::opt_data::OptData::duration).
+    #[default]
+    Continuous,
+    /// Actual/actual ISDA, elapsed time in each calendar year over its length.
+    ActAct,
+    /// Elapsed time over 365 days.
+    Act365Fixed,
+    /// Elapsed time over 360 days.
+    Act360,
+}
+
+impl DayCount {
+    /// # self.year_fraction
+    /// Year fraction from `start` to `end` in this convention, time of day
+    /// included.
+    ///
+    /// # args:
+    /// * `start` - Start of the period.
+    /// * `end` - End of the period.
+    ///
+    /// # returns:
+    /// The year fraction, negative if `end` is before `start`.
+    pub fn year_fraction(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+        let days =
+            |from: DateTime<Utc>, to: DateTime<Utc>| year_fraction(to - from) * DAYS_PER_YEAR;
+        match self {
+            DayCount::Continuous => year_fraction(end - start),
+            DayCount::Act365Fixed => days(start, end) / 365.0,
+            DayCount::Act360 => days(start, end) / 360.0,
+            DayCount::ActAct if end < start => -self.year_fraction(end, start),
+            DayCount::ActAct => (start.year()..=end.year())
+                .map(|year| {
+                    let year_start = |y| Utc.with_ymd_and_hms(y, 1, 1, 0, 0, 0).unwrap();
+                    let length = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+                        366.0
+                    } else {
+                        365.0
+                    };
+                    let from = start.max(year_start(year));
+                    let to = end.min(year_start(year + 1));
+                    days(from, to) / length
+                })
+                .sum(),
+        }
+    }
+}
+
+/// # TradingCalendar
+/// Trading days of an exchange: weekdays that are not holidays.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradingCalendar {
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl TradingCalendar {
+    /// # TradingCalendar::new
+    /// Constructor method for TradingCalendar
+    ///
+    /// # args:
+    /// * `holidays` - Dates the exchange is closed on besides weekends.
+    ///
+    /// # returns:
+    /// Returns a TradingCalendar
+    pub fn new<I: IntoIterator<Item = NaiveDate>>(holidays: I) -> Self {
+        TradingCalendar {
+            holidays: holidays.into_iter().collect(),
+        }
+    }
+
+    /// # self.is_trading_day
+    /// Whether the exchange is open on `date`.
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// # self.next_trading_day
+    /// First trading day strictly after `date`.
+    pub fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        date.iter_days()
+            .skip(1)
+            .find(|d| self.is_trading_day(*d))
+            .expect("dates are exhausted before the next trading day")
+    }
+
+    /// # self.trading_days_between
+    /// Number of trading days in `(from, to]`, i.e. the sessions that pass
+    /// when holding from `from` to `to`. Zero if `to` is not after `from`.
+    pub fn trading_days_between(&self, from: NaiveDate, to: NaiveDate) -> usize {
+        from.iter_days()
+            .skip(1)
+            .take_while(|d| *d <= to)
+            .filter(|d| self.is_trading_day(*d))
+            .count()
+    }
+}
+
+impl Options {
+    /// # self.trading_day_theta
+    /// Theta per trading day. The annual theta is spread over the trading days
+    /// to expiry instead of calendar days, so no decay is attributed to
+    /// weekends and holidays. Rows expiring before the next trading day carry
+    /// their whole remaining decay.
+    ///
+    /// # args:
+    /// * `calendar` - Trading calendar of the exchange.
+    ///
+    /// # returns:
+    /// A theta per row, comparable to the calendar day [`Greeks`](crate::greeks::Greeks)
+    /// theta, or a [`LengthError`] if greeks are not computed.
+    pub fn trading_day_theta(&self, calendar: &TradingCalendar) -> Result<Vec<f64>, LengthError> {
+        let greeks = self.computed_greeks()?;
+        let d = &self.opt_data;
+        Ok((0..d.tickers.len())
+            .map(|i| {
+                let annual = greeks[i].theta * DAYS_PER_YEAR;
+                let days = calendar
+                    .trading_days_between(d.settles[i].date_naive(), d.maturities[i].date_naive());
+                annual * d.duration[i] / days.max(1) as f64
+            })
+            .collect())
+    }
+}
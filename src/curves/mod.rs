@@ -0,0 +1,101 @@
+use crate::opt_data::OptData;
+
+/// # df_to_rate
+/// Converts a discount factor to a continuously compounded rate.
+///
+/// # args:
+/// * `df` - Discount factor for time `t`.
+/// * `t` - Time in years.
+///
+/// # returns:
+/// The rate `-ln(df) / t`, 0.0 for `t <= 0`.
+pub fn df_to_rate(df: f64, t: f64) -> f64 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        -df.ln() / t
+    }
+}
+
+/// # DiscountCurve
+/// Discount factor curve interpolated log-linearly, i.e. with piecewise
+/// flat forward rates. The curve starts at a discount factor of 1.0 at time
+/// 0 and is extrapolated at the zero rate of the last point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscountCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl DiscountCurve {
+    /// # DiscountCurve::new
+    /// Constructor method for DiscountCurve
+    ///
+    /// # args:
+    /// * `points` - `(tenor, discount_factor)` pairs, tenors in years. Sorted by tenor.
+    ///
+    /// # returns:
+    /// Returns a DiscountCurve, or an error if the curve is empty, a tenor is
+    /// not positive, tenors repeat or a discount factor is not positive.
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<Self, String> {
+        if points.is_empty() {
+            return Err("discount curve has no points".to_string());
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (i, (t, df)) in points.iter().enumerate() {
+            if t.is_nan() || df.is_nan() || *t <= 0.0 || *df <= 0.0 {
+                return Err(format!(
+                    "invalid discount curve point ({}, {}), tenor and discount factor must be positive",
+                    t, df
+                ));
+            }
+            if i > 0 && points[i - 1].0 == *t {
+                return Err(format!("duplicate discount curve tenor {}", t));
+            }
+        }
+        Ok(DiscountCurve { points })
+    }
+
+    /// # self.discount_factor
+    /// Discount factor for time `t`.
+    ///
+    /// # args:
+    /// * `t` - Time in years.
+    ///
+    /// # returns:
+    /// The interpolated discount factor, 1.0 for `t <= 0`.
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        if t <= 0.0 {
+            return 1.0;
+        }
+        let (last_t, last_df) = self.points[self.points.len() - 1];
+        if t >= last_t {
+            return (-df_to_rate(last_df, last_t) * t).exp();
+        }
+        let mut prev: (f64, f64) = (0.0, 1.0);
+        for &(tenor, df) in &self.points {
+            if t <= tenor {
+                let w = (t - prev.0) / (tenor - prev.0);
+                return ((1.0 - w) * prev.1.ln() + w * df.ln()).exp();
+            }
+            prev = (tenor, df);
+        }
+        unreachable!("t is below the last tenor")
+    }
+
+    /// # self.rate
+    /// Continuously compounded zero rate for time `t`.
+    pub fn rate(&self, t: f64) -> f64 {
+        df_to_rate(self.discount_factor(t), t)
+    }
+}
+
+impl OptData {
+    /// # self.apply_discount_curve
+    /// Sets every row's risk free rate to the curve's zero rate at the row's duration.
+    ///
+    /// # args:
+    /// * `curve` - Discount factor curve.
+    pub fn apply_discount_curve(&mut self, curve: &DiscountCurve) {
+        self.rfr = self.duration.iter().map(|&t| curve.rate(t)).collect();
+    }
+}
@@ -0,0 +1,178 @@
+use crate::opt_data::{parse_date, year_fraction, OptData, ParseError};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// # DividendSchedule
+/// Discrete cash dividends per ticker, e.g. from reference data kept apart
+/// from the option chain. Applied to an [`OptData`] as continuous yields with
+/// [`OptData::apply_dividend_schedule`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DividendSchedule {
+    schedules: HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+}
+
+impl DividendSchedule {
+    /// # DividendSchedule::new
+    /// Constructor method for DividendSchedule
+    ///
+    /// # args:
+    /// * `dividends` - `(ticker, ex_date, amount)` of every dividend, in any order.
+    ///
+    /// # returns:
+    /// Returns a DividendSchedule.
+    pub fn new(dividends: Vec<(String, DateTime<Utc>, f64)>) -> Self {
+        let mut schedules: HashMap<String, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
+        for (ticker, ex_date, amount) in dividends {
+            schedules.entry(ticker).or_default().push((ex_date, amount));
+        }
+        for schedule in schedules.values_mut() {
+            schedule.sort_by_key(|(ex_date, _)| *ex_date);
+        }
+        DividendSchedule { schedules }
+    }
+
+    /// # DividendSchedule::from_file
+    /// Reads a csv file with `ticker`, `ex_date` and `amount` headers, case
+    /// insensitive and in any order. Dates take any format of [`parse_date`].
+    ///
+    /// # args:
+    /// * `path` - Path to the file.
+    ///
+    /// # returns:
+    /// The schedule, or the first missing column or malformed row.
+    pub fn from_file(path: &PathBuf) -> Result<Self, ParseError> {
+        let contents = fs::read_to_string(path).map_err(ParseError::Io)?;
+        let mut lines = contents.lines();
+        let headers: Vec<String> = lines
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(|h| h.trim().to_lowercase())
+            .collect();
+        let idx = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| ParseError::MissingColumn(name.to_string()))
+        };
+        let (tick_idx, date_idx, amount_idx) = (idx("ticker")?, idx("ex_date")?, idx("amount")?);
+
+        let mut dividends = Vec::new();
+        for (i, line) in lines.enumerate() {
+            // 1-based and counting the header
+            let line_number = i + 2;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let inps: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |idx: usize, name: &str| {
+                inps.get(idx).copied().ok_or_else(|| ParseError::Row {
+                    line: line_number,
+                    reason: format!("missing field {}", name),
+                })
+            };
+            let ticker = field(tick_idx, "ticker")?.to_string();
+            let date = field(date_idx, "ex_date")?;
+            let ex_date = parse_date(date).map_err(|reason| ParseError::Date {
+                line: line_number,
+                field: "ex_date".to_string(),
+                value: date.to_string(),
+                reason,
+            })?;
+            let amount_s = field(amount_idx, "amount")?;
+            let amount = amount_s.parse::<f64>().map_err(|_| ParseError::Row {
+                line: line_number,
+                reason: format!("failed to parse amount '{}' to f64", amount_s),
+            })?;
+            dividends.push((ticker, ex_date, amount));
+        }
+        Ok(DividendSchedule::new(dividends))
+    }
+
+    /// # self.present_value
+    /// Present value at `settle` of the dividends of `ticker` going ex after
+    /// `settle` and no later than `maturity`.
+    ///
+    /// # args:
+    /// * `ticker` - Ticker to look up, tickers without a schedule pay nothing.
+    /// * `settle` - Valuation time.
+    /// * `maturity` - Last ex date included.
+    /// * `rfr` - Continuously compounded rate to discount at.
+    ///
+    /// # returns:
+    /// The discounted sum of the dividends.
+    pub fn present_value(
+        &self,
+        ticker: &str,
+        settle: DateTime<Utc>,
+        maturity: DateTime<Utc>,
+        rfr: f64,
+    ) -> f64 {
+        self.schedules.get(ticker).map_or(0.0, |schedule| {
+            schedule
+                .iter()
+                .filter(|(ex_date, _)| *ex_date > settle && *ex_date <= maturity)
+                .map(|(ex_date, amount)| amount * (-rfr * year_fraction(*ex_date - settle)).exp())
+                .sum()
+        })
+    }
+
+    /// # self.contains
+    /// Whether `ticker` has a schedule, possibly without dividends in a given period.
+    pub fn contains(&self, ticker: &str) -> bool {
+        self.schedules.contains_key(ticker)
+    }
+}
+
+impl OptData {
+    /// # self.apply_dividend_schedule
+    /// Sets the dividend of every row whose ticker has a schedule to the
+    /// continuous yield equivalent to its discrete dividends until maturity,
+    /// `q = -ln(1 - D / S) / T` with `D` their present value, so that
+    /// `S e^(-qT) = S - D`. Rows of other tickers keep their dividend.
+    ///
+    /// Rows whose dividends are worth at least the underlying get a NaN
+    /// yield and are reported in
+    /// [`Options::errors`](crate::options_struct::Options::errors) when priced.
+    ///
+    /// # args:
+    /// * `schedule` - Dividends by ticker.
+    pub fn apply_dividend_schedule(&mut self, schedule: &DividendSchedule) {
+        for i in 0..self.tickers.len() {
+            if !schedule.contains(&self.tickers[i]) {
+                continue;
+            }
+            let pv = schedule.present_value(
+                &self.tickers[i],
+                self.settles[i],
+                self.maturities[i],
+                self.rfr[i],
+            );
+            let t = self.duration[i];
+            self.dividend[i] = if pv == 0.0 || t <= 0.0 {
+                0.0
+            } else if pv >= self.underlying[i] {
+                f64::NAN
+            } else {
+                -(1.0 - pv / self.underlying[i]).ln() / t
+            };
+        }
+    }
+
+    /// # self.attach_dividends
+    /// Reads a dividend schedule from a separate file and applies it, see
+    /// [`DividendSchedule::from_file`] and [`OptData::apply_dividend_schedule`].
+    ///
+    /// # args:
+    /// * `path` - Path to the dividend file.
+    ///
+    /// # returns:
+    /// Ok, or the error reading the dividend file. The rows are unchanged on error.
+    pub fn attach_dividends(&mut self, path: &PathBuf) -> Result<(), ParseError> {
+        let schedule = DividendSchedule::from_file(path)?;
+        self.apply_dividend_schedule(&schedule);
+        Ok(())
+    }
+}
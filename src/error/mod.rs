@@ -0,0 +1,100 @@
+use crate::opt_data::{DateOrderError, ParseError};
+use crate::options_struct::LengthError;
+use crate::units::UnitError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// # OptionsError
+/// Crate wide error type of parsing, pricing and writing. Match on the
+/// variants rather than on messages.
+///
+/// The panic-free entry points are [`OptData::try_new`](crate::opt_data::OptData::try_new),
+/// [`OptData::try_from_file`](crate::opt_data::OptData::try_from_file),
+/// [`try_parse_input`](crate::opt_data::try_parse_input),
+/// [`Options::from_file_with`](crate::options_struct::Options::from_file_with),
+/// [`Options::try_price`](crate::options_struct::Options::try_price) and
+/// [`price_file`](crate::utilities::price_file) / [`price_stream`](crate::utilities::price_stream).
+/// The deprecated file constructors panic on malformed input.
+#[derive(Debug)]
+pub enum OptionsError {
+    /// # OptionsError::Io
+    /// Reading or writing a file or writer failed, including csv encoding.
+    Io(io::Error),
+    /// # OptionsError::Parse
+    /// The input file could not be parsed.
+    Parse(ParseError),
+    /// # OptionsError::Validation
+    /// An input is outside the plausible range of its unit.
+    Validation(UnitError),
+    /// # OptionsError::Pricing
+    /// The pricing model failed, holds the reason.
+    Pricing(String),
+    /// # OptionsError::Inconsistent
+    /// A vector does not have one entry per option.
+    Inconsistent(LengthError),
+    /// # OptionsError::InvalidArgument
+    /// An argument other than the option data is unusable, holds the reason.
+    InvalidArgument(String),
+    /// # OptionsError::DateOrder
+    /// A row matures before its settlement.
+    DateOrder(DateOrderError),
+}
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionsError::Io(e) => write!(f, "io error: {}", e),
+            OptionsError::Parse(e) => write!(f, "parse error: {}", e),
+            OptionsError::Validation(e) => write!(f, "invalid input: {}", e),
+            OptionsError::Pricing(reason) => write!(f, "pricing failed: {}", reason),
+            OptionsError::Inconsistent(e) => write!(f, "inconsistent data: {}", e),
+            OptionsError::InvalidArgument(reason) => write!(f, "invalid argument: {}", reason),
+            OptionsError::DateOrder(e) => write!(f, "invalid dates: {}", e),
+        }
+    }
+}
+
+impl Error for OptionsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OptionsError::Io(e) => Some(e),
+            OptionsError::Parse(e) => Some(e),
+            OptionsError::Validation(e) => Some(e),
+            OptionsError::Pricing(_) => None,
+            OptionsError::Inconsistent(e) => Some(e),
+            OptionsError::InvalidArgument(_) => None,
+            OptionsError::DateOrder(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for OptionsError {
+    fn from(e: io::Error) -> Self {
+        OptionsError::Io(e)
+    }
+}
+
+impl From<csv::Error> for OptionsError {
+    fn from(e: csv::Error) -> Self {
+        OptionsError::Io(e.into())
+    }
+}
+
+impl From<ParseError> for OptionsError {
+    fn from(e: ParseError) -> Self {
+        OptionsError::Parse(e)
+    }
+}
+
+impl From<UnitError> for OptionsError {
+    fn from(e: UnitError) -> Self {
+        OptionsError::Validation(e)
+    }
+}
+
+impl From<LengthError> for OptionsError {
+    fn from(e: LengthError) -> Self {
+        OptionsError::Inconsistent(e)
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use std::ops::{AddAssign, Sub};
 
 // Struct for option greeks
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Greeks {
     pub delta: f64,
     pub gamma: f64,
@@ -24,22 +27,227 @@ impl Default for Greeks {
     }
 }
 
+impl AddAssign for Greeks {
+    /// Adds greeks field by field, e.g. to net positions.
+    fn add_assign(&mut self, other: Greeks) {
+        self.delta += other.delta;
+        self.gamma += other.gamma;
+        self.vega += other.vega;
+        self.theta += other.theta;
+        self.rho += other.rho;
+    }
+}
+
+impl Sub for Greeks {
+    type Output = Greeks;
+
+    /// Subtracts greeks field by field, e.g. to compare two runs.
+    fn sub(self, other: Greeks) -> Greeks {
+        Greeks {
+            delta: self.delta - other.delta,
+            gamma: self.gamma - other.gamma,
+            vega: self.vega - other.vega,
+            theta: self.theta - other.theta,
+            rho: self.rho - other.rho,
+        }
+    }
+}
+
+/// # Compounding
+/// Rate convention rho is expressed in. Prices always discount continuously,
+/// [`Compounding::Annual`] only changes which rate rho is a sensitivity to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compounding {
+    /// Sensitivity to the continuously compounded rate.
+    #[default]
+    Continuous,
+    /// Sensitivity to the annually compounded rate `R = exp(r) - 1`.
+    Annual,
+}
+
+impl Compounding {
+    /// # self.rho
+    /// Converts a rho with respect to the continuous rate to this convention.
+    ///
+    /// # args:
+    /// * `continuous_rho` - Rho with respect to the continuously compounded rate.
+    /// * `rfr` - Continuously compounded risk free rate of the row.
+    ///
+    /// # returns:
+    /// Rho in this convention, with the same per 1% scaling.
+    pub fn rho(&self, continuous_rho: f64, rfr: f64) -> f64 {
+        match self {
+            Compounding::Continuous => continuous_rho,
+            // dr/dR = 1 / (1 + R) = exp(-r)
+            Compounding::Annual => continuous_rho * (-rfr).exp(),
+        }
+    }
+}
+
+/// # BumpedPrices
+/// Prices of an option at a base value of one input and bumped up and down
+/// by `bump` in absolute terms, e.g. spot `S`, `S + h` and `S - h`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BumpedPrices {
+    pub base: f64,
+    pub up: f64,
+    pub down: f64,
+    pub bump: f64,
+}
+
+impl BumpedPrices {
+    /// # self.central
+    /// Central first difference `(up - down) / 2h`, error of order `h^2`.
+    pub fn central(&self) -> f64 {
+        (self.up - self.down) / (2.0 * self.bump)
+    }
+
+    /// # self.forward
+    /// Forward first difference `(up - base) / h`, error of order `h`. Use it
+    /// when the input cannot be bumped down, e.g. at zero volatility.
+    pub fn forward(&self) -> f64 {
+        (self.up - self.base) / self.bump
+    }
+
+    /// # self.second
+    /// Central second difference `(up - 2 base + down) / h^2`.
+    pub fn second(&self) -> f64 {
+        (self.up - 2.0 * self.base + self.down) / (self.bump * self.bump)
+    }
+}
+
+/// Column headers of [`Greeks::to_record`].
+pub const GREEK_HEADERS: [&str; 5] = ["delta", "gamma", "vega", "theta", "rho"];
+
+/// # GreekKind
+/// Enum naming the individual greeks, used to select them generically.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum GreekKind {
+    Delta,
+    Gamma,
+    Vega,
+    Theta,
+    Rho,
+}
+
+impl Greeks {
+    /// # Greeks::finite_difference_from_prices
+    /// Assembles greeks from bumped prices, scaled like the analytic
+    /// Black-Scholes greeks.
+    ///
+    /// Conventions:
+    /// * delta - central difference in spot.
+    /// * gamma - central second difference in spot.
+    /// * vega - central difference in volatility, per 1% change.
+    /// * rho - central difference in the risk free rate, per 1% change.
+    /// * theta - forward difference in time, `aged - spot.base` where `aged`
+    ///   is the price one calendar day later, i.e. per calendar day.
+    ///
+    /// Bumps are absolute, e.g. a volatility bump of `0.0001` is one basis
+    /// point. The `base` of `volatility` and `rfr` is not used.
+    ///
+    /// # args:
+    /// * `spot` - Prices at spot and spot bumped up and down.
+    /// * `volatility` - Prices with volatility bumped up and down.
+    /// * `rfr` - Prices with the risk free rate bumped up and down.
+    /// * `aged` - Price with one calendar day less to maturity.
+    ///
+    /// # returns:
+    /// [`Greeks`] of the option.
+    pub fn finite_difference_from_prices(
+        spot: &BumpedPrices,
+        volatility: &BumpedPrices,
+        rfr: &BumpedPrices,
+        aged: f64,
+    ) -> Greeks {
+        Greeks {
+            delta: spot.central(),
+            gamma: spot.second(),
+            vega: volatility.central() / 100.0,
+            theta: aged - spot.base,
+            rho: rfr.central() / 100.0,
+        }
+    }
+
+    /// # self.get
+    /// Returns the greek named by `kind`.
+    pub fn get(&self, kind: GreekKind) -> f64 {
+        match kind {
+            GreekKind::Delta => self.delta,
+            GreekKind::Gamma => self.gamma,
+            GreekKind::Vega => self.vega,
+            GreekKind::Theta => self.theta,
+            GreekKind::Rho => self.rho,
+        }
+    }
+
+    /// # self.to_record
+    /// Flattens the greeks in [`GREEK_HEADERS`] order.
+    pub fn to_record(&self) -> [String; 5] {
+        [
+            self.delta.to_string(),
+            self.gamma.to_string(),
+            self.vega.to_string(),
+            self.theta.to_string(),
+            self.rho.to_string(),
+        ]
+    }
+
+    /// # self.as_map
+    /// Labels the greeks by their [`GREEK_HEADERS`] name, e.g. to iterate
+    /// over them generically or serialize to arbitrary formats.
+    pub fn as_map(&self) -> BTreeMap<&'static str, f64> {
+        GREEK_HEADERS
+            .into_iter()
+            .zip([self.delta, self.gamma, self.vega, self.theta, self.rho])
+            .collect()
+    }
+}
+
+/// # two_option_hedge
+/// Solves for quantities of two hedge options that neutralize two greeks of
+/// a position at once, e.g. delta and gamma.
+///
+/// # args:
+/// * `position` - Greeks of the position to hedge.
+/// * `hedge_a` - Greeks of one unit of the first hedge option.
+/// * `hedge_b` - Greeks of one unit of the second hedge option.
+/// * `targets` - The two greeks to neutralize.
+///
+/// # returns:
+/// Quantities `(a, b)` to add to the position, or `None` if the system is
+/// singular (the hedge options cannot neutralize both greeks independently).
+pub fn two_option_hedge(
+    position: &Greeks,
+    hedge_a: &Greeks,
+    hedge_b: &Greeks,
+    targets: (GreekKind, GreekKind),
+) -> Option<(f64, f64)> {
+    let (a1, b1, p1) = (
+        hedge_a.get(targets.0),
+        hedge_b.get(targets.0),
+        position.get(targets.0),
+    );
+    let (a2, b2, p2) = (
+        hedge_a.get(targets.1),
+        hedge_b.get(targets.1),
+        position.get(targets.1),
+    );
+    let det = a1 * b2 - a2 * b1;
+    let scale = (a1.abs() + b1.abs()) * (a2.abs() + b2.abs());
+    if !det.is_finite() || det.abs() <= 1e-12 * scale || scale == 0.0 {
+        return None;
+    }
+    // Cramer's rule on a * hedge_a + b * hedge_b = -position
+    Some(((-p1 * b2 + p2 * b1) / det, (-a1 * p2 + a2 * p1) / det))
+}
+
 impl fmt::Display for Greeks {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut str = String::new();
-        str = format!("{} Delta: {:.4} \n", str, self.delta)
-            .parse() // To output Result type
-            .unwrap();
-        str = format!("{} Gamma: {:.4} \n", str, self.gamma)
-            .parse()
-            .unwrap();
-        str = format!("{} Vega: {:.4} \n", str, self.vega)
-            .parse()
-            .unwrap();
-        str = format!("{} Theta: {:.4} \n", str, self.theta)
-            .parse()
-            .unwrap();
-        str = format!("{} Rho: {:.4}", str, self.rho).parse().unwrap();
-        write!(f, "{}", str)
+        writeln!(f, " Delta: {:.4} ", self.delta)?;
+        writeln!(f, " Gamma: {:.4} ", self.gamma)?;
+        writeln!(f, " Vega: {:.4} ", self.vega)?;
+        writeln!(f, " Theta: {:.4} ", self.theta)?;
+        write!(f, " Rho: {:.4}", self.rho)
     }
 }
@@ -1,8 +1,15 @@
 extern crate core;
 
+pub mod arbitrage;
+pub mod calendar;
+pub mod curves;
+pub mod dividends;
+pub mod error;
 pub mod greeks;
 pub mod opt_data;
 pub mod options_struct;
 pub mod pricing_models;
+pub mod structures;
 mod tests;
+pub mod units;
 pub mod utilities;
@@ -1,14 +1,40 @@
-use crate::options_struct::OptTypes;
+use crate::error::OptionsError;
+use crate::options_struct::{Exercise, LengthError, OptTypes};
+use crate::units::{Dividend, Rate, Vol};
 use crate::utilities::retry_open_file;
-use chrono::{DateTime, Utc};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
 use std::fs::File;
 use std::io;
-use std::io::BufRead;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 
-const SEC_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.25;
+/// Days per year of durations, also used to express theta per calendar day
+/// and to convert day counts to years everywhere else in the crate. 365.25 by
+/// default and 365 with the `year-365` feature.
+pub const DAYS_PER_YEAR: f64 = if cfg!(feature = "year-365") {
+    365.0
+} else {
+    365.25
+};
+const SEC_YEAR: f64 = 60.0 * 60.0 * 24.0 * DAYS_PER_YEAR;
+/// Interval between reads of a file without data rows, see [`ParseConfig::max_wait`].
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Contract multiplier used when none is given, standard for equity options.
+pub const DEFAULT_MULTIPLIER: f64 = 100.0;
+/// Currency used when none is given.
+pub const DEFAULT_CURRENCY: &str = "USD";
 
+#[derive(Clone)]
 pub struct OptData {
     /// # OptData
     /// Struct to hold the input data needed to construct options_old.
@@ -18,10 +44,36 @@ pub struct OptData {
     pub strike: Vec<f64>,
     pub settles: Vec<DateTime<Utc>>,
     pub maturities: Vec<DateTime<Utc>>,
+    /// Years to maturity, computed from the dates unless set with
+    /// [`OptData::with_durations`].
     pub duration: Vec<f64>,
+    /// Continuous dividend yield, not a cash amount. Use [`OptData::new_typed`]
+    /// with [`Dividend::Amount`] to convert cash dividends.
     pub dividend: Vec<f64>,
     pub rfr: Vec<f64>,
     pub volatility: Vec<f64>,
+    /// Contract multiplier, e.g. 100 for equity options or 50 for ES.
+    pub multiplier: Vec<f64>,
+    pub currency: Vec<String>,
+    /// Quoted market prices, `None` for rows without a quote.
+    pub market_price: Vec<Option<f64>>,
+    /// Quoted bids, `None` for rows without a bid.
+    pub bid: Vec<Option<f64>>,
+    /// Quoted asks, `None` for rows without an ask.
+    pub ask: Vec<Option<f64>>,
+    /// Exercise style, honored by [`ExerciseDispatchModel`](crate::pricing_models::dispatch::ExerciseDispatchModel).
+    pub exercise: Vec<Exercise>,
+    /// FX rate in units of strike currency per unit of underlying currency,
+    /// 1.0 when both are quoted in the same currency. Applied by
+    /// [`FxAdjustedModel`](crate::pricing_models::fx::FxAdjustedModel).
+    pub fx: Vec<f64>,
+    /// Separately quoted implied vols, `None` for rows without a quote.
+    /// Compared against [`OptData::volatility`] by
+    /// [`Options::vol_consistency`](crate::options_struct::Options::vol_consistency).
+    pub quoted_iv: Vec<Option<f64>>,
+    /// Name of the file each row was read from, `None` for rows not loaded
+    /// with [`OptData::from_dir`].
+    pub source: Vec<Option<String>>,
 }
 
 impl OptData {
@@ -36,13 +88,18 @@ impl OptData {
     /// * `settles` - Vector of settlement times using `chrono::Datetime`.
     /// * `maturities` - Vector of maturity times using `chrono::Datetime`.
     /// * `dividend` - Vector of dividends for the period. Make sure the
-    /// dividends follow the same pattern the pricing model expects. For
-    /// example Black-Scholes assumes continuous dividends for the period.
+    ///   dividends follow the same pattern the pricing model expects. For
+    ///   example Black-Scholes assumes continuous dividends for the period.
     /// * `rfr` - Vector fo risk free interest rate.
     /// * `volatility` - Vector of annualized volatility.
     ///
+    /// A row maturing before its settlement gets a negative duration, which
+    /// prices as NaN. Use [`OptData::try_new`] to reject such rows up front.
+    ///
     /// # returns:
-    /// Returns `OptData` struct.
+    /// Returns `OptData` struct. Multipliers default to [`DEFAULT_MULTIPLIER`]
+    /// and currencies to [`DEFAULT_CURRENCY`], see [`OptData::with_contract_specs`].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tickers: Vec<String>,
         opt_types: Vec<OptTypes>,
@@ -65,37 +122,541 @@ impl OptData {
             dividend,
             rfr,
             volatility,
+            multiplier: Vec::new(),
+            currency: Vec::new(),
+            market_price: Vec::new(),
+            bid: Vec::new(),
+            ask: Vec::new(),
+            exercise: Vec::new(),
+            fx: Vec::new(),
+            quoted_iv: Vec::new(),
+            source: Vec::new(),
         };
         opt_data.duration = opt_data.get_durs();
+        opt_data.multiplier = vec![DEFAULT_MULTIPLIER; opt_data.tickers.len()];
+        opt_data.currency = vec![DEFAULT_CURRENCY.to_string(); opt_data.tickers.len()];
+        opt_data.market_price = vec![None; opt_data.tickers.len()];
+        opt_data.bid = vec![None; opt_data.tickers.len()];
+        opt_data.ask = vec![None; opt_data.tickers.len()];
+        opt_data.exercise = vec![Exercise::European; opt_data.tickers.len()];
+        opt_data.fx = vec![1.0; opt_data.tickers.len()];
+        opt_data.quoted_iv = vec![None; opt_data.tickers.len()];
+        opt_data.source = vec![None; opt_data.tickers.len()];
+        opt_data
+    }
+
+    /// # OptData::try_new
+    /// Like [`OptData::new`], rejecting rows that mature before their
+    /// settlement, see [`OptData::check_date_order`].
+    ///
+    /// # returns:
+    /// Returns `OptData` struct, or one [`DateOrderError`] per offending row.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        tickers: Vec<String>,
+        opt_types: Vec<OptTypes>,
+        underlying: Vec<f64>,
+        strike: Vec<f64>,
+        settles: Vec<DateTime<Utc>>,
+        maturities: Vec<DateTime<Utc>>,
+        dividend: Vec<f64>,
+        rfr: Vec<f64>,
+        volatility: Vec<f64>,
+    ) -> Result<Self, Vec<DateOrderError>> {
+        let opt_data = OptData::new(
+            tickers, opt_types, underlying, strike, settles, maturities, dividend, rfr, volatility,
+        );
+        opt_data.check_date_order()?;
+        Ok(opt_data)
+    }
+
+    /// # self.with_contract_specs
+    /// Replaces the default multipliers and currencies.
+    ///
+    /// # args:
+    /// * `multiplier` - Vector of contract multipliers.
+    /// * `currency` - Vector of currency codes.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_contract_specs(mut self, multiplier: Vec<f64>, currency: Vec<String>) -> Self {
+        self.multiplier = multiplier;
+        self.currency = currency;
+        self
+    }
+
+    /// # self.with_market_prices
+    /// Attaches quoted market prices, used to solve implied volatilities.
+    ///
+    /// # args:
+    /// * `market_price` - Vector of quotes, `None` for rows without a quote.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_market_prices(mut self, market_price: Vec<Option<f64>>) -> Self {
+        self.market_price = market_price;
+        self
+    }
+
+    /// # self.with_quotes
+    /// Attaches quoted bids and asks, used to compute [`Options::edge`](crate::options_struct::Options::edge).
+    ///
+    /// # args:
+    /// * `bid` - Vector of bids, `None` for rows without a bid.
+    /// * `ask` - Vector of asks, `None` for rows without an ask.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_quotes(mut self, bid: Vec<Option<f64>>, ask: Vec<Option<f64>>) -> Self {
+        self.bid = bid;
+        self.ask = ask;
+        self
+    }
+
+    /// # self.with_valuation_time
+    /// Values every row as of a single timestamp, e.g. for end of day marks
+    /// of a whole book. Settles are set to `valuation` and durations are
+    /// recomputed from it to each maturity, replacing per-row settles and
+    /// explicit durations.
+    ///
+    /// # args:
+    /// * `valuation` - As-of time of the valuation.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`, or [`OptionsError::DateOrder`] for the
+    /// first row maturing before `valuation`, see [`OptData::check_date_order`].
+    pub fn with_valuation_time(mut self, valuation: DateTime<Utc>) -> Result<Self, OptionsError> {
+        self.settles = vec![valuation; self.tickers.len()];
+        if let Err(errors) = self.check_date_order() {
+            return Err(OptionsError::DateOrder(errors[0]));
+        }
+        self.duration = self.get_durs();
+        Ok(self)
+    }
+
+    /// # self.with_durations
+    /// Replaces the durations computed from settlement and maturity dates.
+    /// Explicit durations always take precedence over the dates, which are
+    /// kept only for reporting and grouping. Useful to sweep the time to
+    /// maturity as a continuous variable.
+    ///
+    /// # args:
+    /// * `duration` - Vector of durations in years.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`, [`OptionsError::Inconsistent`] if
+    /// `duration` does not have one entry per row, or
+    /// [`OptionsError::InvalidArgument`] for the first negative duration.
+    pub fn with_durations(mut self, duration: Vec<f64>) -> Result<Self, OptionsError> {
+        LengthError::check("duration", self.tickers.len(), duration.len())?;
+        if let Some((i, d)) = duration.iter().enumerate().find(|(_, d)| **d < 0.0) {
+            return Err(OptionsError::InvalidArgument(format!(
+                "negative duration {} at row {}",
+                d, i
+            )));
+        }
+        self.duration = duration;
+        Ok(self)
+    }
+
+    /// # self.with_exercise
+    /// Replaces the default European exercise styles.
+    ///
+    /// # args:
+    /// * `exercise` - Vector of [`Exercise`].
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_exercise(mut self, exercise: Vec<Exercise>) -> Self {
+        self.exercise = exercise;
+        self
+    }
+
+    /// # self.with_fx
+    /// Replaces the default FX rates of 1.0.
+    ///
+    /// # args:
+    /// * `fx` - Vector of FX rates, strike currency per underlying currency.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_fx(mut self, fx: Vec<f64>) -> Self {
+        self.fx = fx;
+        self
+    }
+
+    /// # self.with_quoted_ivs
+    /// Attaches separately quoted implied vols.
+    ///
+    /// # args:
+    /// * `quoted_iv` - Vector of quoted implied vols, `None` for rows without a quote.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_quoted_ivs(mut self, quoted_iv: Vec<Option<f64>>) -> Self {
+        self.quoted_iv = quoted_iv;
+        self
+    }
+
+    /// # self.with_sources
+    /// Tags the rows with the file they were read from.
+    ///
+    /// # args:
+    /// * `source` - Vector of file names, `None` for rows without a source.
+    ///
+    /// # returns:
+    /// Returns the updated `OptData`.
+    pub fn with_sources(mut self, source: Vec<Option<String>>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// # self.slice
+    /// Copies the rows `start..end` into a new OptData.
+    ///
+    /// # args:
+    /// * `start` - First row, inclusive.
+    /// * `end` - Last row, exclusive.
+    ///
+    /// # returns:
+    /// Returns `OptData` struct holding the rows.
+    pub fn slice(&self, start: usize, end: usize) -> OptData {
+        OptData {
+            tickers: self.tickers[start..end].to_vec(),
+            opt_types: self.opt_types[start..end].to_vec(),
+            underlying: self.underlying[start..end].to_vec(),
+            strike: self.strike[start..end].to_vec(),
+            settles: self.settles[start..end].to_vec(),
+            maturities: self.maturities[start..end].to_vec(),
+            duration: self.duration[start..end].to_vec(),
+            dividend: self.dividend[start..end].to_vec(),
+            rfr: self.rfr[start..end].to_vec(),
+            volatility: self.volatility[start..end].to_vec(),
+            multiplier: self.multiplier[start..end].to_vec(),
+            currency: self.currency[start..end].to_vec(),
+            market_price: self.market_price[start..end].to_vec(),
+            bid: self.bid[start..end].to_vec(),
+            ask: self.ask[start..end].to_vec(),
+            exercise: self.exercise[start..end].to_vec(),
+            fx: self.fx[start..end].to_vec(),
+            quoted_iv: self.quoted_iv[start..end].to_vec(),
+            source: self.source[start..end].to_vec(),
+        }
+    }
+
+    /// # OptDat::new_typed
+    /// Literal constructor method for OptData with validated rates and volatilities.
+    /// Guards against passing percentages where decimals are expected.
+    ///
+    /// # args:
+    /// * `tickers` - Vector of strings containing tickers
+    /// * `opt_types` - Vector of [`OptTypes`]
+    /// * `underlying` - Vector of underlying prices.
+    /// * `strike` - Vector of strike prices.
+    /// * `settles` - Vector of settlement times using `chrono::Datetime`.
+    /// * `maturities` - Vector of maturity times using `chrono::Datetime`.
+    /// * `dividend` - Vector of [`Dividend`], either yields or cash amounts.
+    /// * `rfr` - Vector of risk free interest rates as [`Rate`].
+    /// * `volatility` - Vector of annualized volatility as [`Vol`].
+    ///
+    /// # returns:
+    /// Returns `OptData` struct.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_typed(
+        tickers: Vec<String>,
+        opt_types: Vec<OptTypes>,
+        underlying: Vec<f64>,
+        strike: Vec<f64>,
+        settles: Vec<DateTime<Utc>>,
+        maturities: Vec<DateTime<Utc>>,
+        dividend: Vec<Dividend>,
+        rfr: Vec<Rate>,
+        volatility: Vec<Vol>,
+    ) -> Self {
+        let mut opt_data = OptData::new(
+            tickers,
+            opt_types,
+            underlying,
+            strike,
+            settles,
+            maturities,
+            vec![0.0; dividend.len()],
+            rfr.iter().map(Rate::value).collect(),
+            volatility.iter().map(Vol::value).collect(),
+        );
+        // Converting amounts needs the durations computed by new
+        opt_data.dividend = dividend
+            .iter()
+            .enumerate()
+            .map(|(i, d)| d.to_yield(opt_data.underlying[i], opt_data.duration[i]))
+            .collect();
         opt_data
     }
 
+    /// # self.select
+    /// Copies the rows at `indices`, in the given order, into a new OptData.
+    ///
+    /// # args:
+    /// * `indices` - Row indices to copy.
+    ///
+    /// # returns:
+    /// Returns `OptData` struct holding the rows.
+    pub fn select(&self, indices: &[usize]) -> OptData {
+        OptData {
+            tickers: indices.iter().map(|&i| self.tickers[i].clone()).collect(),
+            opt_types: indices.iter().map(|&i| self.opt_types[i]).collect(),
+            underlying: indices.iter().map(|&i| self.underlying[i]).collect(),
+            strike: indices.iter().map(|&i| self.strike[i]).collect(),
+            settles: indices.iter().map(|&i| self.settles[i]).collect(),
+            maturities: indices.iter().map(|&i| self.maturities[i]).collect(),
+            duration: indices.iter().map(|&i| self.duration[i]).collect(),
+            dividend: indices.iter().map(|&i| self.dividend[i]).collect(),
+            rfr: indices.iter().map(|&i| self.rfr[i]).collect(),
+            volatility: indices.iter().map(|&i| self.volatility[i]).collect(),
+            multiplier: indices.iter().map(|&i| self.multiplier[i]).collect(),
+            currency: indices.iter().map(|&i| self.currency[i].clone()).collect(),
+            market_price: indices.iter().map(|&i| self.market_price[i]).collect(),
+            bid: indices.iter().map(|&i| self.bid[i]).collect(),
+            ask: indices.iter().map(|&i| self.ask[i]).collect(),
+            exercise: indices.iter().map(|&i| self.exercise[i]).collect(),
+            fx: indices.iter().map(|&i| self.fx[i]).collect(),
+            quoted_iv: indices.iter().map(|&i| self.quoted_iv[i]).collect(),
+            source: indices.iter().map(|&i| self.source[i].clone()).collect(),
+        }
+    }
+
     /// # OptDat::from_file
     /// Literal constructor method for OptData
     ///
     /// # args:
     /// * `file` -  Path to input file.
     ///
+    /// # panics:
+    /// On the first [`ParseError`], see [`OptData::try_from_file`] for a
+    /// fallible version.
+    ///
     /// # returns:
     /// Returns `OptData` struct.
+    #[deprecated(note = "panics on malformed input, use `OptData::try_from_file`")]
     pub fn from_file(file: &PathBuf) -> Self {
-        let tup = parse_input(file);
-        OptData::new(
-            tup.0, tup.1, tup.2, tup.3, tup.4, tup.5, tup.6, tup.7, tup.8,
-        )
+        match OptData::try_from_file(file, &ParseConfig::default()) {
+            Ok((opt_data, _)) => opt_data,
+            Err(e) => panic!("Unable to parse input: {}", e),
+        }
+    }
+
+    /// # OptDat::from_file_with
+    /// Constructor method for OptData reading a file with a [`ParseConfig`].
+    ///
+    /// # args:
+    /// * `file` -  Path to input file.
+    /// * `config` - Parser configuration, see [`ParseConfig`].
+    ///
+    /// # panics:
+    /// On the first [`ParseError`], see [`OptData::try_from_file`] for a
+    /// fallible version.
+    ///
+    /// # returns:
+    /// Returns `OptData` struct.
+    #[deprecated(note = "panics on malformed input, use `OptData::try_from_file`")]
+    pub fn from_file_with(file: &PathBuf, config: &ParseConfig) -> Self {
+        match OptData::try_from_file(file, config) {
+            Ok((opt_data, _)) => opt_data,
+            Err(e) => panic!("Unable to parse input: {}", e),
+        }
+    }
+
+    /// # OptDat::try_from_file
+    /// Fallible constructor method for OptData reading a file.
+    ///
+    /// # args:
+    /// * `file` -  Path to input file.
+    /// * `config` - Parser configuration, see [`ParseConfig`].
+    ///
+    /// # returns:
+    /// Returns `OptData` struct and the rows skipped in [`ParseMode::Lenient`].
+    pub fn try_from_file(
+        file: &PathBuf,
+        config: &ParseConfig,
+    ) -> Result<(Self, Vec<SkippedRow>), ParseError> {
+        try_parse_input(file, config)
+    }
+
+    /// # OptData::from_dir
+    /// Reads every file in `dir` whose name matches `pattern` and concatenates
+    /// the rows, ordered by file name. Files are parsed in parallel with the
+    /// default [`ParseConfig`] and every row is tagged with its file name in
+    /// [`OptData::source`]. At most one thread per available core is spawned,
+    /// each parsing a contiguous run of the files.
+    ///
+    /// # args:
+    /// * `dir` - Directory to search, not recursively.
+    /// * `pattern` - File name pattern, `*` matches any run of characters and
+    ///   `?` any single one, e.g. `chain_*.csv`.
+    ///
+    /// # returns:
+    /// The rows of all matching files, empty if none match, or the error of
+    /// the first failing file by name wrapped in [`ParseError::File`]. A
+    /// parser thread panicking fails its files with a [`ParseError::Io`].
+    pub fn from_dir(dir: &Path, pattern: &str) -> Result<OptData, ParseError> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map_err(ParseError::Io)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .is_some_and(|name| wildcard_match(pattern, &name.to_string_lossy()))
+            })
+            .collect();
+        files.sort();
+
+        let workers = thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(files.len())
+            .max(1);
+        let per_worker = files.len().div_ceil(workers).max(1);
+        let parsed: Vec<Result<OptData, ParseError>> = thread::scope(|s| {
+            let handles: Vec<_> = files
+                .chunks(per_worker)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|path| {
+                                try_parse_input(path, &ParseConfig::default())
+                                    .map(|(data, _)| data)
+                                    .map_err(|error| ParseError::File {
+                                        path: path.clone(),
+                                        error: Box::new(error),
+                                    })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .zip(files.chunks(per_worker))
+                .flat_map(|(h, chunk)| {
+                    h.join().unwrap_or_else(|_| {
+                        chunk
+                            .iter()
+                            .map(|path| {
+                                Err(ParseError::File {
+                                    path: path.clone(),
+                                    error: Box::new(ParseError::Io(io::Error::other(
+                                        "parser thread panicked",
+                                    ))),
+                                })
+                            })
+                            .collect()
+                    })
+                })
+                .collect()
+        });
+
+        let mut opt_data = OptData::default();
+        for (path, data) in files.iter().zip(parsed) {
+            let data = data?;
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+            let n = data.tickers.len();
+            opt_data.append(data.with_sources(vec![name; n]));
+        }
+        Ok(opt_data)
+    }
+
+    /// # self.append
+    /// Moves the rows of `other` to the end of `self`.
+    ///
+    /// # args:
+    /// * `other` - Rows to append.
+    pub fn append(&mut self, other: OptData) {
+        self.tickers.extend(other.tickers);
+        self.opt_types.extend(other.opt_types);
+        self.underlying.extend(other.underlying);
+        self.strike.extend(other.strike);
+        self.settles.extend(other.settles);
+        self.maturities.extend(other.maturities);
+        self.duration.extend(other.duration);
+        self.dividend.extend(other.dividend);
+        self.rfr.extend(other.rfr);
+        self.volatility.extend(other.volatility);
+        self.multiplier.extend(other.multiplier);
+        self.currency.extend(other.currency);
+        self.market_price.extend(other.market_price);
+        self.bid.extend(other.bid);
+        self.ask.extend(other.ask);
+        self.exercise.extend(other.exercise);
+        self.fx.extend(other.fx);
+        self.quoted_iv.extend(other.quoted_iv);
+        self.source.extend(other.source);
+    }
+
+    /// # self.find_duplicates
+    /// Groups rows describing the same contract, identified by ticker, option
+    /// type, strike and maturity. Other fields such as volatility are ignored,
+    /// duplicates usually double count risk.
+    ///
+    /// # returns:
+    /// Row indices of every group with more than one row, ordered by first row.
+    pub fn find_duplicates(&self) -> Vec<Vec<usize>> {
+        let mut groups: HashMap<(&str, OptTypes, u64, DateTime<Utc>), Vec<usize>> = HashMap::new();
+        for i in 0..self.tickers.len() {
+            let key = (
+                self.tickers[i].as_str(),
+                self.opt_types[i],
+                self.strike[i].to_bits(),
+                self.maturities[i],
+            );
+            groups.entry(key).or_default().push(i);
+        }
+        let mut duplicates: Vec<Vec<usize>> =
+            groups.into_values().filter(|rows| rows.len() > 1).collect();
+        duplicates.sort();
+        duplicates
+    }
+
+    /// # self.check_date_order
+    /// Checks that no maturity is before its settlement. Such a row has a
+    /// negative duration, which silently turns into a NaN price, typically
+    /// after the two date columns were swapped upstream.
+    ///
+    /// # returns:
+    /// `Ok(())`, or one [`DateOrderError`] per offending row, ordered by row.
+    pub fn check_date_order(&self) -> Result<(), Vec<DateOrderError>> {
+        let errors: Vec<DateOrderError> = self
+            .settles
+            .iter()
+            .zip(&self.maturities)
+            .enumerate()
+            .filter(|(_, (settle, maturity))| maturity < settle)
+            .map(|(row, (settle, maturity))| DateOrderError {
+                row,
+                settle: *settle,
+                maturity: *maturity,
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     /// # self.get_durs
     /// Get duration in years from settlement to maturity dates.
+    /// Sub-second precision is kept, which matters for intraday (0DTE) options.
     ///
     /// # returns:
-    /// Returns a vector of durations in years.
+    /// Returns a vector of durations in years, negative for rows maturing
+    /// before their settlement, see [`OptData::check_date_order`].
     fn get_durs(&self) -> Vec<f64> {
-        let mut durs: Vec<f64> = Vec::with_capacity(self.settles.len());
-        for i in 0..self.settles.len() {
-            durs.push((self.maturities[i] - self.settles[i]).num_seconds() as f64 / SEC_YEAR)
-        }
-        durs
+        self.settles
+            .iter()
+            .zip(&self.maturities)
+            .map(|(settle, maturity)| year_fraction(*maturity - *settle))
+            .collect()
     }
 }
 
@@ -117,78 +678,415 @@ impl Default for OptData {
             dividend: vec![],
             rfr: vec![],
             volatility: vec![],
+            multiplier: vec![],
+            currency: vec![],
+            market_price: vec![],
+            bid: vec![],
+            ask: vec![],
+            exercise: vec![],
+            fx: vec![],
+            quoted_iv: vec![],
+            source: vec![],
         }
     }
 }
 
-/// # parse_date
-/// Parses string dates
+/// # wildcard_match
+/// Matches `name` against a pattern where `*` matches any run of characters,
+/// including none, and `?` exactly one.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((sp, sn)) = star {
+            // Let the last `*` swallow one more character
+            p = sp + 1;
+            n = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// # year_fraction
+/// Converts a time delta to years without going through whole seconds.
 ///
 /// # args:
-/// * `s` - A string to parse
+/// * `delta` - Time between two instants.
 ///
 /// # returns:
-/// A chrono compliant string as long as parsing was successful.
-// todo!("stability: add error type if parse unsuccessful")
-fn parse_date(s: &str) -> String {
-    let mut s_ret;
-
-    // The following patterns handle most of Pythons native date types
-    // Regex used to search
-    match &s.find('+') {
-        Some(_) => return s.to_string(),
-        None => {}
-    };
-    match &s.find(r"-\d{2}:\d{2}") {
-        Some(_) => return s.to_string(),
-        None => {}
-    };
-    match &s.find('t') {
-        Some(_) => s_ret = format!("{}{}", s, "+00:00"),
-        None => s_ret = format!("{}{}{}", s, "t00:00:00", "+00:00"),
-    };
-    match &s.find(' ') {
-        Some(i) => {
-            s_ret = format!(
-                "{}{}{}{}",
-                s.to_string().get(0..*i).unwrap(),
-                "t",
-                s.to_string().get(*i + 1..*i + 9).unwrap(),
-                "+00:00"
-            )
+/// The delta in years, including the sub-second part.
+pub(crate) fn year_fraction(delta: TimeDelta) -> f64 {
+    (delta.num_seconds() as f64 + delta.subsec_nanos() as f64 * 1e-9) / SEC_YEAR
+}
+
+/// # ParseMode
+/// How the parser reacts to malformed rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMode {
+    /// # ParseMode::Strict
+    /// Fail on the first malformed row.
+    Strict,
+    /// # ParseMode::Lenient
+    /// Skip malformed rows and report them as `(line_number, reason)`.
+    Lenient,
+}
+
+/// # DateOrderError
+/// A row whose maturity is before its settlement, see
+/// [`OptData::check_date_order`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateOrderError {
+    /// 0-based row index.
+    pub row: usize,
+    pub settle: DateTime<Utc>,
+    pub maturity: DateTime<Utc>,
+}
+
+impl fmt::Display for DateOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {}: maturity {} is before settle {}",
+            self.row, self.maturity, self.settle
+        )
+    }
+}
+
+impl Error for DateOrderError {}
+
+/// A row skipped in [`ParseMode::Lenient`], as `(line_number, reason)`.
+pub type SkippedRow = (usize, String);
+
+/// # ParseError
+/// Errors returned when parsing input files.
+#[derive(Debug)]
+pub enum ParseError {
+    /// # ParseError::Io
+    /// The file could not be opened or read.
+    Io(io::Error),
+    /// # ParseError::MissingColumn
+    /// A required header is absent.
+    MissingColumn(String),
+    /// # ParseError::Row
+    /// A row could not be parsed, `line` is 1-based and counts the header.
+    Row { line: usize, reason: String },
+    /// # ParseError::Date
+    /// A date field of a row could not be parsed, `field` is the column
+    /// name, e.g. settle or maturity, and `value` the offending cell.
+    Date {
+        line: usize,
+        field: String,
+        value: String,
+        reason: String,
+    },
+    /// # ParseError::Duplicate
+    /// Rows describing the same contract with
+    /// [`ParseConfig::reject_duplicates`] set, 1-based line numbers.
+    Duplicate { lines: Vec<usize> },
+    /// # ParseError::File
+    /// An error in one of several files read together, see [`OptData::from_dir`].
+    File {
+        path: PathBuf,
+        error: Box<ParseError>,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "io error: {}", e),
+            ParseError::MissingColumn(name) => write!(f, "no header {} in file", name),
+            ParseError::Row { line, reason } => write!(f, "line {}: {}", line, reason),
+            ParseError::Date {
+                line,
+                field,
+                reason,
+                ..
+            } => write!(f, "line {}: failed to parse {}: {}", line, field, reason),
+            ParseError::Duplicate { lines } => write!(f, "duplicate rows at lines {:?}", lines),
+            ParseError::File { path, error } => write!(f, "{}: {}", path.display(), error),
         }
-        None => {}
     }
-    s_ret
 }
 
-/// # read_lines
-/// Convenience function to get lines of file
+impl Error for ParseError {}
+
+/// # RowError
+/// Error of a single row before its line number is known.
+enum RowError {
+    Invalid(String),
+    Date {
+        field: &'static str,
+        value: String,
+        reason: String,
+    },
+}
+
+impl From<String> for RowError {
+    fn from(reason: String) -> Self {
+        RowError::Invalid(reason)
+    }
+}
+
+impl RowError {
+    /// # self.at_line
+    /// Attaches the line number, see [`ParseError::Row`] and [`ParseError::Date`].
+    fn at_line(self, line: usize) -> ParseError {
+        match self {
+            RowError::Invalid(reason) => ParseError::Row { line, reason },
+            RowError::Date {
+                field,
+                value,
+                reason,
+            } => ParseError::Date {
+                line,
+                field: field.to_string(),
+                value,
+                reason,
+            },
+        }
+    }
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowError::Invalid(reason) => write!(f, "{}", reason),
+            RowError::Date { field, reason, .. } => {
+                write!(f, "failed to parse {}: {}", field, reason)
+            }
+        }
+    }
+}
+
+/// # ParseConfig
+/// Configuration for [`parse_input_with`] and [`try_parse_input`].
+///
+/// The `dividend` and `rfr` columns are optional in input files. When the
+/// header is absent every row is filled with `default_dividend` and
+/// `default_rfr` respectively. Set `override_rates` to use the defaults even
+/// when the columns are present, e.g. to sweep a global rate over a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseConfig {
+    /// Dividend used when the file has no `dividend` column.
+    pub default_dividend: f64,
+    /// Risk free rate used when the file has no `rfr` column.
+    pub default_rfr: f64,
+    /// Fill every row with `default_dividend` and `default_rfr`, ignoring
+    /// the `dividend` and `rfr` columns of the file.
+    pub override_rates: bool,
+    /// Handling of malformed rows, see [`ParseMode`].
+    pub mode: ParseMode,
+    /// Zone of timestamps without an offset, see [`SourceTimezone`].
+    pub timezone: SourceTimezone,
+    /// Solve implied vols from quoted market prices while loading, used by
+    /// [`Options::from_file_with`](crate::options_struct::Options::from_file_with).
+    pub solve_implied_vols: bool,
+    /// Reject files holding the same contract twice, see
+    /// [`OptData::find_duplicates`] and [`ParseError::Duplicate`].
+    pub reject_duplicates: bool,
+    /// Column order of files without a header row. When set every line,
+    /// including the first, is read as data. `None` reads the header.
+    pub columns: Option<Vec<Field>>,
+    /// How long to wait for data rows in a file that has none yet, e.g. one
+    /// still being written upstream. A file still without data rows after
+    /// the wait parses to an empty [`OptData`].
+    pub max_wait: Duration,
+}
+
+/// # Field
+/// Input column, used to give the column order of header-less files in
+/// [`ParseConfig::columns`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Ticker,
+    OptType,
+    Underlying,
+    Strike,
+    Settle,
+    Maturity,
+    Dividend,
+    Rfr,
+    Volatility,
+    Multiplier,
+    Currency,
+    MarketPrice,
+    Bid,
+    Ask,
+    Exercise,
+    Fx,
+    Duration,
+    QuotedIv,
+    /// A column that is not read.
+    Ignore,
+}
+
+impl Field {
+    /// # self.name
+    /// Header name of the column, empty for [`Field::Ignore`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Ticker => "ticker",
+            Field::OptType => "opt_type",
+            Field::Underlying => "underlying",
+            Field::Strike => "strike",
+            Field::Settle => "settle",
+            Field::Maturity => "maturity",
+            Field::Dividend => "dividend",
+            Field::Rfr => "rfr",
+            Field::Volatility => "volatility",
+            Field::Multiplier => "multiplier",
+            Field::Currency => "currency",
+            Field::MarketPrice => "market_price",
+            Field::Bid => "bid",
+            Field::Ask => "ask",
+            Field::Exercise => "exercise",
+            Field::Fx => "fx",
+            Field::Duration => "duration",
+            Field::QuotedIv => "quoted_iv",
+            Field::Ignore => "",
+        }
+    }
+}
+
+/// # SourceTimezone
+/// Zone in which timestamps and dates without an explicit offset are read
+/// before conversion to UTC. Timestamps with an offset are never shifted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SourceTimezone {
+    /// Naive timestamps are UTC.
+    #[default]
+    Utc,
+    /// Naive timestamps are at a fixed offset from UTC.
+    Fixed(FixedOffset),
+    /// Naive timestamps are in the system zone, honoring daylight saving.
+    /// Set the `TZ` environment variable, e.g. `America/New_York`, to read
+    /// exchange local data.
+    Local,
+    /// Naive timestamps are in a named zone, honoring daylight saving, e.g.
+    /// `chrono_tz::America::New_York` for exchange local data.
+    Named(Tz),
+}
+
+impl Default for ParseConfig {
+    /// # default
+    /// Default parser configuration, missing dividend and rfr columns are read
+    /// as 0.0 and the first malformed row is an error.
+    fn default() -> Self {
+        ParseConfig {
+            default_dividend: 0.0,
+            default_rfr: 0.0,
+            override_rates: false,
+            mode: ParseMode::Strict,
+            timezone: SourceTimezone::Utc,
+            solve_implied_vols: false,
+            reject_duplicates: false,
+            columns: None,
+            max_wait: Duration::ZERO,
+        }
+    }
+}
+
+/// Formats of timestamps with an offset not covered by RFC3339, tried in order.
+const OFFSET_DATETIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+];
+
+/// Formats of timestamps without an offset, tried in order.
+const NAIVE_DATETIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dt%H:%M:%S%.f",
+];
+
+/// # parse_date
+/// Parses string dates. Handles most of Pythons native date types:
+/// RFC3339 with any offset, timestamps without an offset and bare dates.
+/// Timestamps without an offset are read as UTC, bare dates as midnight UTC.
+///
+/// Never panics, malformed input of any kind returns an error.
 ///
 /// # args:
-/// * `filename` - Path to file.
+/// * `s` - A string to parse
 ///
 /// # returns:
-/// Returnes a buffer containing the lines of the file.
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// The parsed date in UTC, or a description of why parsing failed.
+pub fn parse_date(s: &str) -> Result<DateTime<Utc>, String> {
+    parse_date_in(s, &Utc)
 }
 
-/// # parse_input
-/// Parses a file for OptData inputs
+/// # parse_date_in
+/// Parses string dates like [`parse_date`], reading timestamps without an
+/// offset and bare dates in `tz`. Accepts any [`TimeZone`], including zones
+/// from `chrono-tz`.
+///
+/// Local times skipped by a daylight saving transition are an error, repeated
+/// ones resolve to the earlier instant.
 ///
 /// # args:
-/// * `path` - Path to the file to parse.
+/// * `s` - A string to parse
+/// * `tz` - Zone of timestamps without an offset.
 ///
 /// # returns:
-/// A tuple of vectors used to initialize [`OptData`]
-pub fn parse_input(
-    path: &PathBuf,
-) -> (
+/// The parsed date in UTC, or a description of why parsing failed.
+pub fn parse_date_in<Tz: TimeZone>(s: &str, tz: &Tz) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty date".to_string());
+    }
+    // Full RFC3339 first so explicit offsets, including negative ones, are kept
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    // Space separated as written by pandas, or offsets without a colon
+    if let Some(dt) = OFFSET_DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| DateTime::parse_from_str(s, fmt).ok())
+    {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let naive = NAIVE_DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+        .ok_or_else(|| format!("unrecognized date '{}'", s))?;
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| format!("nonexistent local time '{}'", s))
+}
+
+/// # parse_date_config
+/// Parses string dates with [`parse_date_in`] in the zone of `timezone`.
+fn parse_date_config(s: &str, timezone: SourceTimezone) -> Result<DateTime<Utc>, String> {
+    match timezone {
+        SourceTimezone::Utc => parse_date_in(s, &Utc),
+        SourceTimezone::Fixed(offset) => parse_date_in(s, &offset),
+        SourceTimezone::Local => parse_date_in(s, &Local),
+        SourceTimezone::Named(tz) => parse_date_in(s, &tz),
+    }
+}
+
+/// Column vectors returned by [`parse_input`], in [`OptData::new`] argument order.
+type ParsedColumns = (
     Vec<String>,
     Vec<OptTypes>,
     Vec<f64>,
@@ -198,120 +1096,496 @@ pub fn parse_input(
     Vec<f64>,
     Vec<f64>,
     Vec<f64>,
-) {
-    // Initializing variables
-    let mut file;
-    let mut lines_num;
+);
+
+/// # parse_input
+/// Parses a file for OptData inputs
+///
+/// # args:
+/// * `path` - Path to the file to parse.
+///
+/// # panics:
+/// On the first [`ParseError`], see [`try_parse_input`] for a fallible version.
+///
+/// # returns:
+/// A tuple of vectors used to initialize [`OptData`]
+#[deprecated(note = "panics on malformed input, use `try_parse_input`")]
+pub fn parse_input(path: &PathBuf) -> ParsedColumns {
+    #[allow(deprecated)]
+    parse_input_with(path, &ParseConfig::default())
+}
+
+/// # parse_input_with
+/// Parses a file for OptData inputs using a [`ParseConfig`]
+///
+/// # args:
+/// * `path` - Path to the file to parse.
+/// * `config` - Parser configuration. Supplies values for optional columns.
+///
+/// # panics:
+/// On the first [`ParseError`], see [`try_parse_input`] for a fallible version.
+///
+/// # returns:
+/// A tuple of vectors used to initialize [`OptData`]
+#[deprecated(note = "panics on malformed input, use `try_parse_input`")]
+pub fn parse_input_with(path: &PathBuf, config: &ParseConfig) -> ParsedColumns {
+    match try_parse_input(path, config) {
+        Ok((d, _)) => (
+            d.tickers,
+            d.opt_types,
+            d.underlying,
+            d.strike,
+            d.settles,
+            d.maturities,
+            d.dividend,
+            d.rfr,
+            d.volatility,
+        ),
+        Err(e) => panic!("Unable to parse input: {}", e),
+    }
+}
+
+/// # column_idx
+/// Finds the index of a header, case insensitive.
+fn column_idx(headers: &[&str], name: &str) -> Option<usize> {
+    headers.iter().position(|x| x.to_lowercase() == name)
+}
+
+/// # required_column_idx
+/// Finds the index of a header that has to be present in the file.
+fn required_column_idx(headers: &[&str], name: &str) -> Result<usize, ParseError> {
+    column_idx(headers, name).ok_or_else(|| ParseError::MissingColumn(name.to_string()))
+}
+
+/// # field
+/// Gets a field of a split line, erroring if the line is too short.
+fn field<'a>(inps: &[&'a str], idx: usize, name: &str) -> Result<&'a str, String> {
+    inps.get(idx)
+        .copied()
+        .ok_or_else(|| format!("missing field {}", name))
+}
+
+/// # parse_f64
+/// Parses a float field of a split line.
+fn parse_f64(inps: &[&str], idx: usize, name: &str) -> Result<f64, String> {
+    let s = field(inps, idx, name)?;
+    s.trim()
+        .parse::<f64>()
+        .map_err(|_| format!("failed to parse {} '{}' to f64", name, s))
+}
+
+/// # parse_rate
+/// Parses a decimal or percentage field of a split line, "35%" is read as 0.35.
+fn parse_rate(inps: &[&str], idx: usize, name: &str) -> Result<f64, String> {
+    let s = field(inps, idx, name)?;
+    match s.trim().strip_suffix('%') {
+        Some(pct) => pct
+            .trim()
+            .parse::<f64>()
+            .map(|v| v / 100.0)
+            .map_err(|_| format!("failed to parse {} '{}' to f64", name, s)),
+        None => parse_f64(inps, idx, name),
+    }
+}
+
+/// # parse_optional_f64
+/// Parses an optional float column of a split line, absent columns and
+/// empty cells are `None`.
+fn parse_optional_f64(
+    inps: &[&str],
+    idx: Option<usize>,
+    name: &str,
+) -> Result<Option<f64>, String> {
+    match idx {
+        Some(idx) if !field(inps, idx, name)?.trim().is_empty() => {
+            Ok(Some(parse_f64(inps, idx, name)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// # parse_datetime
+/// Parses a date field of a split line.
+fn parse_datetime(
+    inps: &[&str],
+    idx: usize,
+    name: &'static str,
+    timezone: SourceTimezone,
+) -> Result<DateTime<Utc>, RowError> {
+    let s = field(inps, idx, name)?;
+    parse_date_config(s, timezone).map_err(|reason| RowError::Date {
+        field: name,
+        value: s.to_string(),
+        reason,
+    })
+}
 
-    // Compute number of lines
+/// # try_parse_input
+/// Parses a file for OptData inputs, returning errors instead of panicking.
+///
+/// With [`ParseMode::Strict`] the first malformed row is returned as an error.
+/// With [`ParseMode::Lenient`] malformed rows are skipped and reported.
+///
+/// # args:
+/// * `path` - Path to the file to parse.
+/// * `config` - Parser configuration, see [`ParseConfig`].
+///
+/// The optional `multiplier` and `currency` columns default to
+/// [`DEFAULT_MULTIPLIER`] and [`DEFAULT_CURRENCY`]. An optional `market_price`
+/// column is read into [`OptData::market_price`], empty cells are `None`,
+/// and likewise optional `bid` and `ask` columns into [`OptData::bid`] and [`OptData::ask`].
+/// An optional `exercise` column takes "american" or "european" and
+/// defaults to European. An optional `fx` column defaults to 1.0.
+/// An optional `quoted_iv` column is read into [`OptData::quoted_iv`] like
+/// volatility, empty cells are `None`.
+/// An optional `duration` column in years takes precedence over the
+/// settle and maturity dates, empty cells fall back to the dates.
+/// A `sigma` header is accepted in place of `volatility`. Volatility, rfr and
+/// dividend take decimals or percentages with a trailing `%`.
+/// Dates without an offset are read in [`ParseConfig::timezone`].
+/// With [`ParseConfig::columns`] set the file has no header row and columns
+/// are read in the given order, a missing required field is an error.
+/// A file with a header but no data rows gives an empty [`OptData`] once
+/// [`ParseConfig::max_wait`] has passed.
+/// With [`ParseConfig::reject_duplicates`] the first group of duplicate rows
+/// is an error, in either mode.
+///
+/// # returns:
+/// The parsed [`OptData`] and the skipped rows as `(line_number, reason)`,
+/// line numbers are 1-based and count the header if any.
+pub fn try_parse_input(
+    path: &PathBuf,
+    config: &ParseConfig,
+) -> Result<(OptData, Vec<SkippedRow>), ParseError> {
+    let start = Instant::now();
+    // Wait up to max_wait for data rows
     loop {
-        file = retry_open_file(path).unwrap();
-        // parse number of lines in file
-        lines_num = 0;
-        for _ in file.lines().skip(1) {
-            lines_num += 1;
+        let (opt_data, skipped, lines_num) = parse_once(path, config)?;
+        if lines_num > 0 || start.elapsed() >= config.max_wait {
+            return Ok((opt_data, skipped));
         }
-        if lines_num > 0 {
-            break;
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// # ChunkReader
+/// Reads a file in chunks of rows, so files larger than memory can be
+/// processed chunk by chunk. Rows are parsed like in [`try_parse_input`],
+/// [`ParseConfig::reject_duplicates`] applies within each chunk and
+/// [`ParseConfig::max_wait`] is ignored.
+pub struct ChunkReader {
+    lines: io::Lines<BufReader<File>>,
+    header: String,
+    next_line: usize,
+    chunk_size: usize,
+    config: ParseConfig,
+    done: bool,
+}
+
+impl ChunkReader {
+    /// # ChunkReader::new
+    /// Opens a file and reads its header.
+    ///
+    /// # args:
+    /// * `path` - Path to the file to parse.
+    /// * `chunk_size` - Lines per chunk, clamped to at least 1.
+    /// * `config` - Parser configuration, see [`ParseConfig`].
+    ///
+    /// # returns:
+    /// A `ChunkReader` positioned at the first data line.
+    pub fn new(
+        path: &PathBuf,
+        chunk_size: usize,
+        config: &ParseConfig,
+    ) -> Result<Self, ParseError> {
+        let (lines, header) = open_lines(path, config)?;
+        Ok(ChunkReader {
+            lines,
+            header,
+            next_line: 1 + usize::from(config.columns.is_none()),
+            chunk_size: chunk_size.max(1),
+            config: config.clone(),
+            done: false,
+        })
+    }
+}
+
+impl Iterator for ChunkReader {
+    /// A chunk and the rows skipped in it, or the error ending the file.
+    type Item = Result<(OptData, Vec<SkippedRow>), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let chunk = parse_rows(
+            &self.header,
+            &mut self.lines,
+            self.next_line,
+            self.chunk_size,
+            &self.config,
+        );
+        match chunk {
+            Ok((_, _, 0)) => {
+                self.done = true;
+                None
+            }
+            Ok((opt_data, skipped, lines_num)) => {
+                self.next_line += lines_num;
+                Some(Ok((opt_data, skipped)))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
         }
     }
+}
 
-    println!("Processing {} options", &lines_num);
+/// # parse_once
+/// Reads the file once, pushing rows into the columns as they are read.
+///
+/// # returns:
+/// The parsed [`OptData`], the skipped rows and the number of data lines read.
+fn parse_once(
+    path: &PathBuf,
+    config: &ParseConfig,
+) -> Result<(OptData, Vec<SkippedRow>, usize), ParseError> {
+    let (mut lines, header) = open_lines(path, config)?;
+    let first_line = 1 + usize::from(config.columns.is_none());
+    parse_rows(&header, &mut lines, first_line, usize::MAX, config)
+}
 
-    // Get index position of column containing appropriate data
-    if let Ok(mut lines) = read_lines(&path) {
-        let procc_s = lines.next().unwrap().unwrap();
-        let headers: Vec<&str> = procc_s.split(',').collect();
-        let tick_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "ticker")
-            .expect("No header tickers in file");
-        let opt_t_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "opt_type")
-            .expect("No header opt_type in file");
-        let underlying_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "underlying")
-            .expect("No header underlying in file");
-        let strike_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "strike")
-            .expect("No header strike in file");
-        let set_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "settle")
-            .expect("No header settle in file");
-        let mat_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "maturity")
-            .expect("No header maturity in file");
-        let dividend_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "dividend")
-            .expect("No header dividend in file");
-        let rfr_idx = headers
-            .iter()
-            .position(|x| x.to_lowercase() == "rfr")
-            .expect("No header rfr in file");
-        let volatility_idx = headers
+/// # open_lines
+/// Opens a file and reads its header, or builds it from [`ParseConfig::columns`].
+///
+/// # returns:
+/// The remaining lines and the header.
+fn open_lines(
+    path: &PathBuf,
+    config: &ParseConfig,
+) -> Result<(io::Lines<BufReader<File>>, String), ParseError> {
+    let mut lines = retry_open_file(path)
+        .ok_or_else(|| {
+            ParseError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("unable to open {}", path.display()),
+            ))
+        })?
+        .lines();
+    let header = match &config.columns {
+        // Header-less files, the header is built from the given order
+        Some(columns) => columns
             .iter()
-            .position(|x| x.to_lowercase() == "volatility")
-            .expect("No header volatility in file");
-
-        // initializing Vectors
-        let mut tickers: Vec<String> = Vec::with_capacity(lines_num);
-        let mut opt_types: Vec<OptTypes> = Vec::with_capacity(lines_num);
-        let mut underlying: Vec<f64> = Vec::with_capacity(lines_num);
-        let mut strike: Vec<f64> = Vec::with_capacity(lines_num);
-        let mut settles: Vec<DateTime<Utc>> = Vec::with_capacity(lines_num);
-        let mut maturities: Vec<DateTime<Utc>> = Vec::with_capacity(lines_num);
-        let mut dividend: Vec<f64> = Vec::with_capacity(lines_num);
-        let mut rfr: Vec<f64> = Vec::with_capacity(lines_num);
-        let mut volatility: Vec<f64> = Vec::with_capacity(lines_num);
-
-        // push data
-        for line in lines.flatten() {
-            let inps: Vec<&str> = line.split(',').collect();
-            tickers.push(inps[tick_idx].to_string());
-            opt_types.push(OptTypes::from_str(inps[opt_t_idx]).unwrap());
-            underlying.push(
-                inps[underlying_idx]
-                    .parse::<f64>()
-                    .expect("failed to parse s to f64"),
-            );
-            strike.push(
-                inps[strike_idx]
-                    .parse::<f64>()
-                    .expect("failed to parse k to f64"),
-            );
-            settles.push(DateTime::from(
-                DateTime::parse_from_rfc3339(&*parse_date(inps[set_idx])).unwrap(),
-            ));
-            maturities.push(DateTime::from(
-                DateTime::parse_from_rfc3339(&*parse_date(inps[mat_idx])).unwrap(),
-            ));
-            dividend.push(
-                inps[dividend_idx]
-                    .parse::<f64>()
-                    .expect("failed to parse s to f64"),
-            );
-            rfr.push(
-                inps[rfr_idx]
-                    .parse::<f64>()
-                    .expect("failed to parse s to f64"),
-            );
-            volatility.push(
-                inps[volatility_idx]
-                    .parse::<f64>()
-                    .expect("failed to parse s to f64"),
-            );
+            .map(Field::name)
+            .collect::<Vec<_>>()
+            .join(","),
+        None => match lines.next() {
+            Some(header) => header.map_err(ParseError::Io)?,
+            None => return Err(ParseError::MissingColumn("ticker".to_string())),
+        },
+    };
+    Ok((lines, header))
+}
+
+/// # parse_rows
+/// Parses up to `limit` lines into an [`OptData`].
+///
+/// # args:
+/// * `header` - Header of the file, locates the columns.
+/// * `lines` - Data lines, advanced by the lines read.
+/// * `first_line` - 1-based line number of the next line, used in errors.
+/// * `limit` - Maximum number of lines to read.
+/// * `config` - Parser configuration.
+///
+/// # returns:
+/// The parsed [`OptData`], the skipped rows and the number of lines read.
+fn parse_rows<I>(
+    header: &str,
+    lines: &mut I,
+    first_line: usize,
+    limit: usize,
+    config: &ParseConfig,
+) -> Result<(OptData, Vec<SkippedRow>, usize), ParseError>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    // Get index position of column containing appropriate data
+    let headers: Vec<&str> = header.split(',').map(|x| x.trim()).collect();
+    let tick_idx = required_column_idx(&headers, "ticker")?;
+    let opt_t_idx = required_column_idx(&headers, "opt_type")?;
+    let underlying_idx = required_column_idx(&headers, "underlying")?;
+    let strike_idx = required_column_idx(&headers, "strike")?;
+    let set_idx = required_column_idx(&headers, "settle")?;
+    let mat_idx = required_column_idx(&headers, "maturity")?;
+    // Optional columns, filled from config when absent or overridden
+    let dividend_idx = column_idx(&headers, "dividend").filter(|_| !config.override_rates);
+    let rfr_idx = column_idx(&headers, "rfr").filter(|_| !config.override_rates);
+    // Files written for the older sigma naming are read the same way
+    let volatility_idx = column_idx(&headers, "volatility")
+        .or_else(|| column_idx(&headers, "sigma"))
+        .ok_or_else(|| ParseError::MissingColumn("volatility".to_string()))?;
+    let multiplier_idx = column_idx(&headers, "multiplier");
+    let currency_idx = column_idx(&headers, "currency");
+    let market_price_idx = column_idx(&headers, "market_price");
+    let bid_idx = column_idx(&headers, "bid");
+    let ask_idx = column_idx(&headers, "ask");
+    let exercise_idx = column_idx(&headers, "exercise");
+    let fx_idx = column_idx(&headers, "fx");
+    let duration_idx = column_idx(&headers, "duration");
+    let quoted_iv_idx = column_idx(&headers, "quoted_iv");
+
+    // initializing Vectors
+    let mut tickers: Vec<String> = Vec::new();
+    let mut opt_types: Vec<OptTypes> = Vec::new();
+    let mut underlying: Vec<f64> = Vec::new();
+    let mut strike: Vec<f64> = Vec::new();
+    let mut settles: Vec<DateTime<Utc>> = Vec::new();
+    let mut maturities: Vec<DateTime<Utc>> = Vec::new();
+    let mut dividend: Vec<f64> = Vec::new();
+    let mut rfr: Vec<f64> = Vec::new();
+    let mut volatility: Vec<f64> = Vec::new();
+    let mut multiplier: Vec<f64> = Vec::new();
+    let mut currency: Vec<String> = Vec::new();
+    let mut market_price: Vec<Option<f64>> = Vec::new();
+    let mut bid: Vec<Option<f64>> = Vec::new();
+    let mut ask: Vec<Option<f64>> = Vec::new();
+    let mut exercise: Vec<Exercise> = Vec::new();
+    let mut fx: Vec<f64> = Vec::new();
+    let mut duration: Vec<Option<f64>> = Vec::new();
+    let mut quoted_iv: Vec<Option<f64>> = Vec::new();
+    let mut skipped: Vec<SkippedRow> = Vec::new();
+    let mut row_lines: Vec<usize> = Vec::new();
+    let mut lines_num = 0;
+
+    // push data
+    for (i, line) in lines.take(limit).enumerate() {
+        lines_num += 1;
+        let line_number = first_line + i; // 1-based, after the header if any
+        let line = line.map_err(ParseError::Io)?;
+        let inps: Vec<&str> = line.split(',').collect();
+
+        // Parse the whole row before pushing so columns stay aligned
+        let row = (|| -> Result<_, RowError> {
+            let ticker = field(&inps, tick_idx, "ticker")?.to_string();
+            let opt_type_s = field(&inps, opt_t_idx, "opt_type")?;
+            let opt_type = OptTypes::from_str(opt_type_s.trim())
+                .map_err(|_| format!("failed to parse opt_type '{}'", opt_type_s))?;
+            let settle = parse_datetime(&inps, set_idx, "settle", config.timezone)?;
+            let maturity = parse_datetime(&inps, mat_idx, "maturity", config.timezone)?;
+            if maturity < settle {
+                return Err(RowError::Invalid(format!(
+                    "maturity {} is before settle {}",
+                    maturity, settle
+                )));
+            }
+            let explicit_duration = parse_optional_f64(&inps, duration_idx, "duration")?;
+            if explicit_duration.is_some_and(|d| d < 0.0) {
+                return Err(RowError::Invalid("negative duration".to_string()));
+            }
+            Ok((
+                ticker,
+                opt_type,
+                parse_f64(&inps, underlying_idx, "underlying")?,
+                parse_f64(&inps, strike_idx, "strike")?,
+                settle,
+                maturity,
+                match dividend_idx {
+                    Some(idx) => parse_rate(&inps, idx, "dividend")?,
+                    None => config.default_dividend,
+                },
+                match rfr_idx {
+                    Some(idx) => parse_rate(&inps, idx, "rfr")?,
+                    None => config.default_rfr,
+                },
+                parse_rate(&inps, volatility_idx, "volatility")?,
+                match multiplier_idx {
+                    Some(idx) => parse_f64(&inps, idx, "multiplier")?,
+                    None => DEFAULT_MULTIPLIER,
+                },
+                match currency_idx {
+                    Some(idx) => field(&inps, idx, "currency")?.trim().to_string(),
+                    None => DEFAULT_CURRENCY.to_string(),
+                },
+                parse_optional_f64(&inps, market_price_idx, "market_price")?,
+                match exercise_idx {
+                    Some(idx) => {
+                        let exercise_s = field(&inps, idx, "exercise")?;
+                        Exercise::from_str(exercise_s.trim())
+                            .map_err(|_| format!("failed to parse exercise '{}'", exercise_s))?
+                    }
+                    None => Exercise::European,
+                },
+                match fx_idx {
+                    Some(idx) => parse_f64(&inps, idx, "fx")?,
+                    None => 1.0,
+                },
+                parse_optional_f64(&inps, bid_idx, "bid")?,
+                parse_optional_f64(&inps, ask_idx, "ask")?,
+                explicit_duration,
+                match quoted_iv_idx {
+                    Some(idx) if !field(&inps, idx, "quoted_iv")?.trim().is_empty() => {
+                        Some(parse_rate(&inps, idx, "quoted_iv")?)
+                    }
+                    _ => None,
+                },
+            ))
+        })();
+
+        match row {
+            Ok(row) => {
+                tickers.push(row.0);
+                opt_types.push(row.1);
+                underlying.push(row.2);
+                strike.push(row.3);
+                settles.push(row.4);
+                maturities.push(row.5);
+                dividend.push(row.6);
+                rfr.push(row.7);
+                volatility.push(row.8);
+                multiplier.push(row.9);
+                currency.push(row.10);
+                market_price.push(row.11);
+                exercise.push(row.12);
+                fx.push(row.13);
+                bid.push(row.14);
+                ask.push(row.15);
+                duration.push(row.16);
+                quoted_iv.push(row.17);
+                row_lines.push(line_number);
+            }
+            Err(e) => match config.mode {
+                ParseMode::Strict => return Err(e.at_line(line_number)),
+                ParseMode::Lenient => skipped.push((line_number, e.to_string())),
+            },
+        }
+    }
+    // Rows are checked above, this only guards the assembled columns
+    let mut opt_data = OptData::try_new(
+        tickers, opt_types, underlying, strike, settles, maturities, dividend, rfr, volatility,
+    )
+    .map_err(|errors| ParseError::Row {
+        line: row_lines[errors[0].row],
+        reason: errors[0].to_string(),
+    })?
+    .with_contract_specs(multiplier, currency)
+    .with_market_prices(market_price)
+    .with_exercise(exercise)
+    .with_fx(fx)
+    .with_quotes(bid, ask)
+    .with_quoted_ivs(quoted_iv);
+    // Explicit durations win over the ones computed from dates, negative ones
+    // are rejected per row above
+    opt_data.duration = duration
+        .iter()
+        .zip(&opt_data.duration)
+        .map(|(explicit, from_dates)| explicit.unwrap_or(*from_dates))
+        .collect();
+    if config.reject_duplicates {
+        if let Some(rows) = opt_data.find_duplicates().first() {
+            let lines = rows.iter().map(|&i| row_lines[i]).collect();
+            return Err(ParseError::Duplicate { lines });
         }
-        // Return tuple of columns
-        (
-            tickers, opt_types, underlying, strike, settles, maturities, dividend, rfr, volatility,
-        )
-    } else {
-        panic!("Unable to parse input.")
     }
+    Ok((opt_data, skipped, lines_num))
 }
@@ -1,7 +1,15 @@
-use crate::greeks::Greeks;
-use crate::opt_data::OptData;
+use crate::error::OptionsError;
+use crate::greeks::{two_option_hedge, GreekKind, Greeks, GREEK_HEADERS};
+use crate::opt_data::{OptData, ParseConfig, SkippedRow, DAYS_PER_YEAR};
 use crate::pricing_models::PricingModel;
+use crate::utilities::bisect;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
 use std::path::PathBuf;
 
 use crate::pricing_models::black_scholes::BlackScholesModel;
@@ -10,7 +18,15 @@ use std::str::FromStr;
 
 /// # OptTypes
 /// Enum representing option types.
-#[derive(PartialEq, Eq, Clone, Copy)]
+///
+/// Ordered by declaration, calls sort before puts. Relied on for
+/// deterministic grouping and output, do not reorder the variants.
+///
+/// Non exhaustive as more option types are planned, prefer
+/// [`OptTypes::is_call`], [`OptTypes::is_put`] and [`OptTypes::payoff`]
+/// over matching on the variants.
+#[non_exhaustive]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
 pub enum OptTypes {
     /// # OptTypes::Call
     /// Enum type representing calls.
@@ -20,6 +36,46 @@ pub enum OptTypes {
     Put,
 }
 
+impl OptTypes {
+    /// # self.is_call
+    /// Whether the option is a call.
+    pub fn is_call(&self) -> bool {
+        *self == OptTypes::Call
+    }
+
+    /// # self.is_put
+    /// Whether the option is a put.
+    pub fn is_put(&self) -> bool {
+        *self == OptTypes::Put
+    }
+
+    /// # self.sign
+    /// Direction of the payoff, +1 for calls and -1 for puts. Closed form
+    /// prices and greeks of both types follow from one formula with it.
+    pub fn sign(&self) -> f64 {
+        match self {
+            OptTypes::Call => 1.0,
+            OptTypes::Put => -1.0,
+        }
+    }
+
+    /// # self.payoff
+    /// Intrinsic value of the option at expiry.
+    ///
+    /// # args:
+    /// * `spot` - Price of the underlying.
+    /// * `strike` - Strike price.
+    ///
+    /// # returns:
+    /// The payoff, never negative.
+    pub fn payoff(&self, spot: f64, strike: f64) -> f64 {
+        match self {
+            OptTypes::Call => (spot - strike).max(0.0),
+            OptTypes::Put => (strike - spot).max(0.0),
+        }
+    }
+}
+
 // Implementing trait FromStr to parse OptTypes
 impl FromStr for OptTypes {
     /// # FromStr
@@ -35,26 +91,360 @@ impl FromStr for OptTypes {
     }
 }
 
-// Implementing trait ToString to parse OptTypes
-impl ToString for OptTypes {
-    /// # ToString
-    /// Implements ToString to output strings from OptTypes. Used for writing files.
-    fn to_string(&self) -> String {
+// Implementing trait Display to parse OptTypes
+impl fmt::Display for OptTypes {
+    /// # Display
+    /// Implements Display to output strings from OptTypes. Used for writing files.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptTypes::Put => write!(f, "Put"),
+            OptTypes::Call => write!(f, "Call"),
+        }
+    }
+}
+
+/// Column headers of [`Options::to_records`], used when writing csv.
+pub const RECORD_HEADERS: [&str; 18] = [
+    "ticker",
+    "opt_type",
+    "underlying",
+    "strike",
+    "settle",
+    "maturity",
+    "duration",
+    "dividend",
+    "rfr",
+    "volatility",
+    "multiplier",
+    "currency",
+    "price",
+    "delta",
+    "gamma",
+    "vega",
+    "theta",
+    "rho",
+];
+
+/// # Exercise
+/// Enum representing exercise styles.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+pub enum Exercise {
+    /// # Exercise::European
+    /// Exercisable at maturity only.
+    #[default]
+    European,
+    /// # Exercise::American
+    /// Exercisable at any time up to maturity.
+    American,
+}
+
+// Implementing trait FromStr to parse Exercise
+impl FromStr for Exercise {
+    /// # FromStr
+    /// Implements FromStr to construct Exercise from strings.
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_lowercase() as &str {
+            // Case insensitive
+            "european" => Ok(Exercise::European),
+            "american" => Ok(Exercise::American),
+            _ => Err(()),
+        }
+    }
+}
+
+// Implementing trait Display to write Exercise
+impl fmt::Display for Exercise {
+    /// # Display
+    /// Implements Display to output strings from Exercise. Used for writing files.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exercise::European => write!(f, "European"),
+            Exercise::American => write!(f, "American"),
+        }
+    }
+}
+
+/// Bracket searched when solving implied volatilities.
+const IV_BOUNDS: (f64, f64) = (1e-4, 5.0);
+
+/// # LengthError
+/// A vector does not have one entry per option row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthError {
+    /// Name of the offending vector.
+    pub name: &'static str,
+    /// Number of option rows.
+    pub expected: usize,
+    /// Length of the offending vector.
+    pub found: usize,
+}
+
+impl LengthError {
+    /// # LengthError::check
+    /// Errors if `found` differs from `expected`.
+    pub fn check(name: &'static str, expected: usize, found: usize) -> Result<(), LengthError> {
+        if expected == found {
+            Ok(())
+        } else {
+            Err(LengthError {
+                name,
+                expected,
+                found,
+            })
+        }
+    }
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} has length {}, expected {} (one per option)",
+            self.name, self.found, self.expected
+        )
+    }
+}
+
+impl Error for LengthError {}
+
+/// # Residual
+/// Model price against market price of a single row, see [`Options::residuals`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Residual {
+    pub model_price: f64,
+    pub market_price: f64,
+    /// Model minus market price.
+    pub abs_error: f64,
+    /// Model minus market price relative to the market price.
+    pub rel_error: f64,
+    /// Volatility reproducing the market price, NaN if none does.
+    pub implied_vol: f64,
+    /// Implied minus input volatility.
+    pub vol_gap: f64,
+}
+
+/// Vega per 1% below which implied vols are considered unreliable.
+pub const TINY_VEGA: f64 = 1e-4;
+/// Duration in years below which a row is considered about to expire, one day.
+pub const NEAR_ZERO_DURATION: f64 = 1.0 / DAYS_PER_YEAR;
+/// Absolute log moneyness `ln(S/K)` beyond which a row is considered extreme.
+pub const EXTREME_LOG_MONEYNESS: f64 = 1.0;
+
+/// # WarningCategory
+/// Reason a row was flagged as numerically suspicious.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningCategory {
+    /// Vega below [`TINY_VEGA`], implied vols are unreliable.
+    TinyVega,
+    /// Duration below [`NEAR_ZERO_DURATION`].
+    NearZeroDuration,
+    /// Absolute log moneyness beyond [`EXTREME_LOG_MONEYNESS`].
+    ExtremeMoneyness,
+    /// A slightly negative price was floored at zero, see
+    /// [`Options::with_price_floor`].
+    PriceFloored,
+}
+
+impl fmt::Display for WarningCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningCategory::TinyVega => write!(f, "tiny vega"),
+            WarningCategory::NearZeroDuration => write!(f, "near-zero duration"),
+            WarningCategory::ExtremeMoneyness => write!(f, "extreme moneyness"),
+            WarningCategory::PriceFloored => write!(f, "price floored at zero"),
+        }
+    }
+}
+
+/// # PricingWarning
+/// Non-fatal warning for a single row, see [`Options::warnings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PricingWarning {
+    pub row: usize,
+    pub category: WarningCategory,
+}
+
+impl fmt::Display for PricingWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.category)
+    }
+}
+
+/// # PricingError
+/// Error of a single row, see [`Options::errors`]. The other rows are still priced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PricingError {
+    /// An input the models cannot price, e.g. a non-positive strike.
+    InvalidInput { field: &'static str, value: f64 },
+    /// The model returned NaN or an infinite price.
+    NonFinite(f64),
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            OptTypes::Put => "Put".to_string(),
-            OptTypes::Call => "Call".to_string(),
+            PricingError::InvalidInput { field, value } => {
+                write!(f, "invalid {} {}", field, value)
+            }
+            PricingError::NonFinite(price) => write!(f, "non-finite price {}", price),
         }
     }
 }
 
+impl Error for PricingError {}
+
+/// # PricedChain
+/// Results of [`Options::compute`], borrowing the inputs they were computed from.
+#[derive(Clone)]
+pub struct PricedChain<'a> {
+    pub inputs: &'a OptData,
+    pub prices: Vec<f64>,
+    pub greeks: Vec<Greeks>,
+    /// Row errors, see [`Options::errors`].
+    pub errors: Vec<Option<PricingError>>,
+}
+
+/// # Edge
+/// Model price against the quoted spread of a single row, see [`Options::edge`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Edge {
+    /// Mid of bid and ask.
+    pub mid: f64,
+    /// Model price minus mid.
+    pub edge: f64,
+    /// Whether the model price is below the bid or above the ask.
+    pub outside_spread: bool,
+}
+
+/// # AtmSkew
+/// Headline vol surface numbers of one expiry, see [`Options::atm_vol_and_skew`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AtmSkew {
+    pub ticker: String,
+    pub maturity: DateTime<Utc>,
+    /// Implied vol at the forward.
+    pub atm_vol: f64,
+    /// Change of implied vol per unit of log strike at the forward, usually
+    /// negative for equities.
+    pub skew: f64,
+}
+
+/// # ChainSummary
+/// Aggregate statistics of a priced chain for quick QA, see [`Options::summary`].
+/// Statistics over results that are not computed are `None`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChainSummary {
+    pub count: usize,
+    pub calls: usize,
+    pub puts: usize,
+    /// Minimum, maximum and mean of the finite prices.
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub mean_price: Option<f64>,
+    /// Mean of the input volatilities.
+    pub mean_volatility: Option<f64>,
+    /// Mean of the finite solved implied volatilities.
+    pub mean_implied_vol: Option<f64>,
+    /// Sum and mean absolute value of the finite deltas.
+    pub total_delta: Option<f64>,
+    pub mean_abs_delta: Option<f64>,
+    /// Number of rows with a non-finite price or greek.
+    pub non_finite: usize,
+}
+
+impl fmt::Display for ChainSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.4}", v));
+        writeln!(
+            f,
+            "options: {} ({} calls, {} puts), non-finite: {}",
+            self.count, self.calls, self.puts, self.non_finite
+        )?;
+        writeln!(
+            f,
+            "price min/max/mean: {}/{}/{}",
+            opt(self.min_price),
+            opt(self.max_price),
+            opt(self.mean_price)
+        )?;
+        writeln!(
+            f,
+            "mean volatility: {}, mean implied vol: {}",
+            opt(self.mean_volatility),
+            opt(self.mean_implied_vol)
+        )?;
+        write!(
+            f,
+            "total delta: {}, mean abs delta: {}",
+            opt(self.total_delta),
+            opt(self.mean_abs_delta)
+        )
+    }
+}
+
+/// # mean
+/// Mean of the finite values, `None` if there are none.
+fn mean<I: Iterator<Item = f64>>(values: I) -> Option<f64> {
+    let (sum, n) = values
+        .filter(|v| v.is_finite())
+        .fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// # Moneyness
+/// Classification of an option relative to spot, see [`Options::moneyness_class`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Moneyness {
+    InTheMoney,
+    AtTheMoney,
+    OutOfTheMoney,
+}
+
+impl fmt::Display for Moneyness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Moneyness::InTheMoney => write!(f, "ITM"),
+            Moneyness::AtTheMoney => write!(f, "ATM"),
+            Moneyness::OutOfTheMoney => write!(f, "OTM"),
+        }
+    }
+}
+
+/// # Overrides
+/// Optional replacement inputs for repricing a single row with [`Options::price_at`].
+/// Fields left as `None` fall back to the stored values of the row.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Overrides {
+    pub underlying: Option<f64>,
+    pub volatility: Option<f64>,
+    pub rfr: Option<f64>,
+    /// Duration in years.
+    pub duration: Option<f64>,
+}
+
+#[derive(Clone)]
 pub struct Options {
     /// # Options
     /// A struct representing a financial options contract.
+    ///
+    /// Lifecycle: construct with inputs and a model, compute with
+    /// [`Options::get_prices`], [`Options::get_greeks`] and
+    /// [`Options::solve_implied_vols`], read the result vectors, then either
+    /// reprice or reset with [`Options::clear_results`] before mutating
+    /// [`Options::opt_data`] in place, so stale results are not read. Results
+    /// are empty until computed, [`Options::set_model`] clears them as well.
     pub opt_data: OptData,
     pub prices: Vec<f64>,
     pub greeks: Vec<Greeks>,
+    /// Implied volatilities solved from [`OptData::market_price`], NaN where
+    /// a row has no quote or no volatility reproduces it.
+    pub implied_vol: Vec<f64>,
+    /// Errors of the rows priced by [`Options::get_prices`], `None` for rows
+    /// with a valid price.
+    pub errors: Vec<Option<PricingError>>,
     model: Box<dyn PricingModel + Send>,
-    iter_count: usize,
+    floor_prices: bool,
 }
 
 impl Options {
@@ -70,8 +460,10 @@ impl Options {
             opt_data,
             prices: Vec::new(),
             greeks: Vec::new(),
+            implied_vol: Vec::new(),
+            errors: Vec::new(),
             model,
-            iter_count: 0,
+            floor_prices: true,
         }
     }
 
@@ -81,23 +473,268 @@ impl Options {
     /// # args:
     /// * `input_file` - Path to input file.
     /// * `model` - Pricing model used to compute options_old. Has to implement PricingModel and Send.
-
+    ///
+    /// # panics:
+    /// On the first [`ParseError`](crate::opt_data::ParseError), see
+    /// [`Options::from_file_with`] for a fallible version.
+    ///
     /// # returns:
     /// Returns an `Options` struct.
+    #[deprecated(note = "panics on malformed input, use `Options::from_file_with`")]
     pub fn from_file(input_file: &PathBuf, model: Box<dyn PricingModel + Send>) -> Self {
+        #[allow(deprecated)]
+        let opt_data = OptData::from_file(input_file);
         Options {
-            opt_data: OptData::from_file(input_file),
+            opt_data,
             prices: Vec::new(),
             greeks: Vec::new(),
+            implied_vol: Vec::new(),
+            errors: Vec::new(),
             model,
-            iter_count: 0,
+            floor_prices: true,
         }
     }
 
+    /// # Options::from_file_with
+    /// Constructs options from file with a [`ParseConfig`], without panicking.
+    /// With [`ParseConfig::solve_implied_vols`] set and quoted market prices in
+    /// the file, implied vols are solved into [`Options::implied_vol`] next to
+    /// the input volatilities.
+    ///
+    /// # args:
+    /// * `input_file` - Path to input file.
+    /// * `model` - Pricing model, also used to solve implied vols.
+    /// * `config` - Parser configuration.
+    ///
+    /// # returns:
+    /// The `Options` and the rows skipped in lenient mode.
+    pub fn from_file_with(
+        input_file: &PathBuf,
+        model: Box<dyn PricingModel + Send>,
+        config: &ParseConfig,
+    ) -> Result<(Self, Vec<SkippedRow>), OptionsError> {
+        let (opt_data, skipped) = OptData::try_from_file(input_file, config)?;
+        let mut opt = Options::new(opt_data, model);
+        if config.solve_implied_vols && opt.opt_data.market_price.iter().any(Option::is_some) {
+            opt.solve_implied_vols();
+        }
+        Ok((opt, skipped))
+    }
+
+    /// # self.with_price_floor
+    /// Sets whether negative prices are floored at zero, on by default. An
+    /// option is never worth less than nothing, negative prices only come
+    /// from numerical error in deep out of the money rows. Turn it off to
+    /// keep the raw model output.
+    ///
+    /// # args:
+    /// * `floor_prices` - Whether to floor prices at zero.
+    ///
+    /// # returns:
+    /// Returns the updated `Options`.
+    pub fn with_price_floor(mut self, floor_prices: bool) -> Self {
+        self.floor_prices = floor_prices;
+        self
+    }
+
+    /// # self.set_model
+    /// Replaces the pricing model. Prices, greeks, implied vols and errors computed
+    /// with the previous model are cleared.
+    ///
+    /// # args:
+    /// * `model` - New pricing model.
+    pub fn set_model(&mut self, model: Box<dyn PricingModel + Send>) {
+        self.model = model;
+        self.clear_results();
+    }
+
+    /// # self.clear_results
+    /// Empties prices, greeks, implied vols and errors, e.g. before reusing
+    /// the struct for another scenario. Inputs and model are kept. Until the
+    /// results are recomputed, methods reading them return a [`LengthError`]
+    /// naming the emptied vector, e.g. `prices` found 0 of n rows, and
+    /// [`Options::select`] copies inputs only.
+    pub fn clear_results(&mut self) {
+        self.prices.clear();
+        self.greeks.clear();
+        self.implied_vol.clear();
+        self.errors.clear();
+    }
+
+    /// # self.validate_inputs
+    /// Checks the inputs against the requirements of the model, see
+    /// [`PricingModel::validate_inputs`].
+    ///
+    /// # returns:
+    /// Ok, or one message per missing or invalid input.
+    pub fn validate_inputs(&self) -> Result<(), Vec<String>> {
+        self.model.validate_inputs(self)
+    }
+
     /// # self.get_prices
-    /// Computes prices based on model provided and stores in self.prices
+    /// Computes prices based on model provided and stores in self.prices.
+    /// Rows with invalid inputs or a non-finite price are recorded in
+    /// self.errors instead of aborting, their price is NaN. Negative prices
+    /// are floored at zero unless disabled, see [`Options::with_price_floor`].
     pub fn get_prices(&mut self) {
-        self.prices = self.model.get_price(self);
+        (self.prices, self.errors, _) = self.checked_prices(self.model.get_price(self));
+    }
+
+    /// # self.try_price
+    /// Computes and stores prices and greeks like [`Options::get_prices`] and
+    /// [`Options::get_greeks`], without panicking on bad data. Inputs are
+    /// checked with [`Options::validate_inputs`] first, and model results
+    /// without one entry per row are rejected instead of indexed.
+    ///
+    /// # returns:
+    /// Ok, or [`OptionsError::Inconsistent`] for vectors of the wrong length
+    /// and [`OptionsError::Pricing`] with the validation messages.
+    pub fn try_price(&mut self) -> Result<(), OptionsError> {
+        self.check_consistency()?;
+        self.validate_inputs()
+            .map_err(|errors| OptionsError::Pricing(errors.join("; ")))?;
+        let n = self.opt_data.tickers.len();
+        let prices = self.model.get_price(self);
+        LengthError::check("prices", n, prices.len())?;
+        let greeks = self.model.get_greeks(self);
+        LengthError::check("greeks", n, greeks.len())?;
+        (self.prices, self.errors, _) = self.checked_prices(prices);
+        self.greeks = greeks;
+        Ok(())
+    }
+
+    /// # self.compute
+    /// Computes prices and greeks without storing them, so a shared chain
+    /// can be priced through `&self`, also from several threads since
+    /// `Options` is `Sync`. Errors are recorded like in [`Options::get_prices`].
+    ///
+    /// # returns:
+    /// A [`PricedChain`] borrowing the inputs.
+    pub fn compute(&self) -> PricedChain<'_> {
+        let (prices, errors, _) = self.checked_prices(self.model.get_price(self));
+        PricedChain {
+            inputs: &self.opt_data,
+            prices,
+            greeks: self.model.get_greeks(self),
+            errors,
+        }
+    }
+
+    /// # self.checked_prices
+    /// Validates the model prices of every row, see [`Options::errors`].
+    /// Also returns the rows floored at zero, see [`Options::with_price_floor`].
+    fn checked_prices(
+        &self,
+        mut prices: Vec<f64>,
+    ) -> (Vec<f64>, Vec<Option<PricingError>>, Vec<usize>) {
+        let errors: Vec<Option<PricingError>> = prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| self.row_error(i, *price))
+            .collect();
+        let mut floored = Vec::new();
+        for (i, (price, error)) in prices.iter_mut().zip(&errors).enumerate() {
+            if error.is_some() {
+                *price = f64::NAN;
+            } else if self.floor_prices && *price < 0.0 {
+                *price = 0.0;
+                floored.push(i);
+            }
+        }
+        (prices, errors, floored)
+    }
+
+    /// # self.row_error
+    /// Validates the inputs and the computed price of a single row.
+    fn row_error(&self, i: usize, price: f64) -> Option<PricingError> {
+        let d = &self.opt_data;
+        let invalid = [
+            ("underlying", d.underlying[i], d.underlying[i] > 0.0),
+            // A zero strike prices as a prepaid forward
+            ("strike", d.strike[i], d.strike[i] >= 0.0),
+            ("volatility", d.volatility[i], d.volatility[i] >= 0.0),
+            ("duration", d.duration[i], d.duration[i] >= 0.0),
+            ("rfr", d.rfr[i], d.rfr[i].is_finite()),
+            ("dividend", d.dividend[i], d.dividend[i].is_finite()),
+        ]
+        .into_iter()
+        .find(|(_, value, valid)| !valid || !value.is_finite());
+        match invalid {
+            Some((field, value, _)) => Some(PricingError::InvalidInput { field, value }),
+            None if !price.is_finite() => Some(PricingError::NonFinite(price)),
+            None => None,
+        }
+    }
+
+    /// # self.failed_rows
+    /// Rows that could not be priced, see [`Options::errors`].
+    ///
+    /// # returns:
+    /// Pairs of row index and error, ordered by row.
+    pub fn failed_rows(&self) -> Vec<(usize, PricingError)> {
+        self.errors
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.map(|e| (i, e)))
+            .collect()
+    }
+
+    /// # self.get_prices_with_warnings
+    /// Computes prices like [`Options::get_prices`] and appends warnings for
+    /// numerically suspicious rows to `warnings`, see [`Options::warnings`].
+    /// Rows whose price was floored at zero are flagged as
+    /// [`WarningCategory::PriceFloored`].
+    ///
+    /// # args:
+    /// * `warnings` - Collector the warnings are appended to.
+    pub fn get_prices_with_warnings(&mut self, warnings: &mut Vec<PricingWarning>) {
+        let floored;
+        (self.prices, self.errors, floored) = self.checked_prices(self.model.get_price(self));
+        let mut flagged = self.warnings();
+        flagged.extend(floored.into_iter().map(|row| PricingWarning {
+            row,
+            category: WarningCategory::PriceFloored,
+        }));
+        // Stable, keeps the category order within a row
+        flagged.sort_by_key(|w| w.row);
+        warnings.extend(flagged);
+    }
+
+    /// # self.warnings
+    /// Flags rows with tiny vega, near-zero duration or extreme moneyness.
+    /// Rows are never rejected, a row can be flagged for several categories.
+    /// Vega is read from the stored greeks when computed, otherwise from the
+    /// stored model. The vega check is skipped if the model has no greeks.
+    ///
+    /// # returns:
+    /// Warnings ordered by row.
+    pub fn warnings(&self) -> Vec<PricingWarning> {
+        let data = &self.opt_data;
+        let n = data.tickers.len();
+        let vegas: Option<Vec<f64>> = match self.computed_greeks() {
+            Ok(greeks) => Some(greeks.iter().map(|g| g.vega).collect()),
+            Err(_) => {
+                let greeks = self.model.get_greeks(self);
+                (greeks.len() == n).then(|| greeks.iter().map(|g| g.vega).collect())
+            }
+        };
+        let mut warnings = Vec::new();
+        for row in 0..n {
+            let mut flag = |category| warnings.push(PricingWarning { row, category });
+            if let Some(vega) = vegas.as_ref().map(|v| v[row]) {
+                if vega.is_nan() || vega < TINY_VEGA {
+                    flag(WarningCategory::TinyVega);
+                }
+            }
+            if data.duration[row] < NEAR_ZERO_DURATION {
+                flag(WarningCategory::NearZeroDuration);
+            }
+            let moneyness = (data.underlying[row] / data.strike[row]).ln();
+            if moneyness.is_nan() || moneyness.abs() > EXTREME_LOG_MONEYNESS {
+                flag(WarningCategory::ExtremeMoneyness);
+            }
+        }
+        warnings
     }
 
     /// # self.get_greeks
@@ -106,72 +743,1046 @@ impl Options {
         self.greeks = self.model.get_greeks(self);
     }
 
-    /// # self.to_records
-    /// Flattens option data (deserialize to vector of flat records)
+    /// # self.get_greeks_for
+    /// Computes greeks only for the rows at `indices` and stores them in self.greeks.
+    ///
+    /// If self.greeks does not hold one entry per row it is reset to
+    /// [`Greeks::default`] for every row first. Rows not in `indices` keep their
+    /// previous value, so un-computed rows are either stale or all zero greeks.
+    ///
+    /// # args:
+    /// * `indices` - Row indices to compute, panics if any is out of bounds.
+    pub fn get_greeks_for(&mut self, indices: &[usize]) {
+        let n = self.opt_data.tickers.len();
+        if self.greeks.len() != n {
+            self.greeks = vec![Greeks::default(); n];
+        }
+        let subset = Options {
+            opt_data: self.opt_data.select(indices),
+            ..Options::default()
+        };
+        let computed = self.model.get_greeks(&subset);
+        for (&i, greeks) in indices.iter().zip(computed) {
+            self.greeks[i] = greeks;
+        }
+    }
+
+    /// # self.price_at
+    /// Reprices a single row at modified inputs using the stored model.
+    /// Stored data is left untouched.
+    ///
+    /// # args:
+    /// * `idx` - Row index to reprice.
+    /// * `overrides` - Inputs replacing the stored ones, see [`Overrides`].
+    ///
+    /// # returns:
+    /// Price of the row at the overridden inputs, `None` if `idx` is out of
+    /// bounds or the model returns no price.
+    pub fn price_at(&self, idx: usize, overrides: Overrides) -> Option<f64> {
+        if idx >= self.opt_data.tickers.len() {
+            return None;
+        }
+        let mut row_data = self.opt_data.slice(idx, idx + 1);
+        if let Some(underlying) = overrides.underlying {
+            row_data.underlying[0] = underlying;
+        }
+        if let Some(volatility) = overrides.volatility {
+            row_data.volatility[0] = volatility;
+        }
+        if let Some(rfr) = overrides.rfr {
+            row_data.rfr[0] = rfr;
+        }
+        if let Some(duration) = overrides.duration {
+            row_data.duration[0] = duration;
+        }
+        let row = Options::new(row_data, self.model.clone_box());
+        self.model.get_price(&row).first().copied()
+    }
+
+    /// # self.price_with_vols
+    /// Prices every row at the given volatilities using the stored model,
+    /// e.g. to test a vol set during calibration. Stored data, prices and
+    /// errors are left untouched, like in [`Options::price_at`].
+    ///
+    /// # args:
+    /// * `vols` - Volatility per row, replacing [`OptData::volatility`].
+    ///
+    /// # returns:
+    /// The raw model prices, or a [`LengthError`] if `vols` does not have
+    /// one entry per row.
+    pub fn price_with_vols(&self, vols: &[f64]) -> Result<Vec<f64>, LengthError> {
+        LengthError::check("vols", self.opt_data.tickers.len(), vols.len())?;
+        let data = OptData {
+            volatility: vols.to_vec(),
+            ..self.opt_data.clone()
+        };
+        let bumped = Options::new(data, self.model.clone_box());
+        Ok(self.model.get_price(&bumped))
+    }
+
+    /// # self.scenario_rate
+    /// Rate scenario ladder: reprices every row with the stored model at the
+    /// risk free rate shifted by each of `rate_shifts`, everything else fixed.
+    /// Models with a rate only path, see [`PricingModel::rate_shift_prices`],
+    /// reuse everything but the drift and discount terms between scenarios,
+    /// e.g. [`BlackScholesModel`]. Other models reprice the chain in full per
+    /// scenario. Stored data, prices and errors are left untouched, like in
+    /// [`Options::price_at`].
+    ///
+    /// # args:
+    /// * `rate_shifts` - Parallel rate shifts as decimals, e.g. 0.0025 for 25bp.
+    ///
+    /// # returns:
+    /// Per shift, the price change of every row against the unshifted rates.
+    pub fn scenario_rate(&self, rate_shifts: &[f64]) -> Vec<Vec<f64>> {
+        let shifts: Vec<f64> = std::iter::once(0.0)
+            .chain(rate_shifts.iter().copied())
+            .collect();
+        let mut ladder = self
+            .model
+            .rate_shift_prices(self, &shifts)
+            .unwrap_or_else(|| {
+                self.rate_scenarios(&shifts, |scenario| self.model.get_price(scenario))
+            });
+        let base = ladder.remove(0);
+        ladder
+            .iter()
+            .map(|prices| {
+                prices
+                    .iter()
+                    .zip(&base)
+                    .map(|(price, base)| price - base)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// # self.scenario_rate_greeks
+    /// Greek changes of the rate scenario ladder, see [`Options::scenario_rate`].
+    ///
+    /// # args:
+    /// * `rate_shifts` - Parallel rate shifts as decimals, e.g. 0.0025 for 25bp.
+    ///
+    /// # returns:
+    /// Per shift, the greeks of every row minus its greeks at the unshifted rates.
+    pub fn scenario_rate_greeks(&self, rate_shifts: &[f64]) -> Vec<Vec<Greeks>> {
+        let base = self.model.get_greeks(self);
+        self.rate_scenarios(rate_shifts, |scenario| {
+            self.model
+                .get_greeks(scenario)
+                .iter()
+                .zip(&base)
+                .map(|(greeks, base)| *greeks - *base)
+                .collect()
+        })
+    }
+
+    /// # self.rate_scenarios
+    /// Evaluates `f` on a copy of the chain per shift of the risk free rate.
+    /// Only the rates of the copy are rewritten between shifts.
+    fn rate_scenarios<T, F>(&self, rate_shifts: &[f64], f: F) -> Vec<T>
+    where
+        F: Fn(&Options) -> T,
+    {
+        let mut scenario = Options::new(self.opt_data.clone(), self.model.clone_box());
+        rate_shifts
+            .iter()
+            .map(|shift| {
+                for (r, base_r) in scenario.opt_data.rfr.iter_mut().zip(&self.opt_data.rfr) {
+                    *r = base_r + shift;
+                }
+                f(&scenario)
+            })
+            .collect()
+    }
+
+    /// # self.vega_for_shift
+    /// Vega over a finite vol move, e.g. the size of a risk limit. Reprices
+    /// every row with the stored model at `volatility +/- vol_points` and
+    /// reports half the price difference. Unlike scaling [`Greeks::vega`],
+    /// which is per vol point, this includes how vega changes along the
+    /// move, which matters for large shifts and far out of the money rows.
+    ///
+    /// Shifted vols are clamped at zero. A row whose down leg is clamped is
+    /// repriced at zero vol instead and the difference is rescaled from the
+    /// clamped span to the requested move.
+    ///
+    /// # args:
+    /// * `vol_points` - Size of the move in vol points, e.g. 5.0 for 5 vols.
+    ///
+    /// # returns:
+    /// The price change per row for a `vol_points` move, 0.0 for rows whose
+    /// legs are both clamped.
+    pub fn vega_for_shift(&self, vol_points: f64) -> Vec<f64> {
+        let shift = vol_points / 100.0;
+        let legs = |sign: f64| -> Vec<f64> {
+            self.opt_data
+                .volatility
+                .iter()
+                .map(|v| (v + sign * shift).max(0.0))
+                .collect()
+        };
+        let (up_vols, down_vols) = (legs(1.0), legs(-1.0));
+        let price = |vols: &[f64]| {
+            self.price_with_vols(vols)
+                .expect("Shifted vols have one entry per row.")
+        };
+        price(&up_vols)
+            .into_iter()
+            .zip(price(&down_vols))
+            .zip(up_vols.iter().zip(&down_vols))
+            .map(|((up, down), (up_vol, down_vol))| {
+                let span = up_vol - down_vol;
+                if span == 0.0 {
+                    0.0
+                } else {
+                    (up - down) * shift / span
+                }
+            })
+            .collect()
+    }
+
+    /// # self.age
+    /// Advances the valuation date by `days` calendar days and reprices.
+    /// Durations shrink by `days` over [`DAYS_PER_YEAR`], the year basis of
+    /// [`OptData::duration`], clamped at 0, and settles move forward up to
+    /// maturity. Comparing against the current prices gives the realized
+    /// decay, weekends included, instead of the closed form theta.
+    ///
+    /// # args:
+    /// * `days` - Calendar days to advance, fractions allowed.
+    ///
+    /// # returns:
+    /// Aged [`Options`] with prices and greeks computed by the stored model.
+    pub fn age(&self, days: f64) -> Options {
+        let mut data = self.opt_data.clone();
+        let shift = TimeDelta::nanoseconds((days * 86_400.0 * 1e9) as i64);
+        for i in 0..data.tickers.len() {
+            data.duration[i] = (data.duration[i] - days / DAYS_PER_YEAR).max(0.0);
+            data.settles[i] = (data.settles[i] + shift).min(data.maturities[i]);
+        }
+        let mut aged =
+            Options::new(data, self.model.clone_box()).with_price_floor(self.floor_prices);
+        aged.get_prices();
+        aged.get_greeks();
+        aged
+    }
+
+    /// # self.solve_implied_vols
+    /// Solves the implied volatility of every row with a market price using
+    /// the stored model and stores them in self.implied_vol. The input
+    /// volatilities are left untouched.
+    pub fn solve_implied_vols(&mut self) {
+        let implied_vol = (0..self.opt_data.tickers.len())
+            .map(|i| match self.opt_data.market_price[i] {
+                Some(market) => self.implied_vol_at(i, market),
+                None => f64::NAN,
+            })
+            .collect();
+        self.implied_vol = implied_vol;
+    }
+
+    /// # self.implied_vol_at
+    /// Solves the volatility at which the stored model reproduces `market` for a row.
+    ///
+    /// # args:
+    /// * `idx` - Row index.
+    /// * `market` - Price to reproduce.
+    ///
+    /// # returns:
+    /// The implied volatility, NaN if no volatility reproduces the price.
+    pub fn implied_vol_at(&self, idx: usize, market: f64) -> f64 {
+        bisect(
+            |vol| {
+                self.price_at(
+                    idx,
+                    Overrides {
+                        volatility: Some(vol),
+                        ..Overrides::default()
+                    },
+                )
+                .unwrap_or(f64::NAN)
+                    - market
+            },
+            IV_BOUNDS.0,
+            IV_BOUNDS.1,
+            1e-10,
+        )
+        .unwrap_or(f64::NAN)
+    }
+
+    /// # self.residuals
+    /// Compares model prices to market prices row by row.
+    ///
+    /// # args:
+    /// * `market` - Market prices, one per row.
+    ///
+    /// # returns:
+    /// A [`Residual`] per row, or a [`LengthError`] if `market` or the
+    /// computed prices do not match the number of rows.
+    pub fn residuals(&self, market: &[f64]) -> Result<Vec<Residual>, LengthError> {
+        let n = self.opt_data.tickers.len();
+        LengthError::check("prices", n, self.prices.len())?;
+        LengthError::check("market", n, market.len())?;
+        Ok((0..n)
+            .map(|i| {
+                let implied_vol = self.implied_vol_at(i, market[i]);
+                Residual {
+                    model_price: self.prices[i],
+                    market_price: market[i],
+                    abs_error: self.prices[i] - market[i],
+                    rel_error: (self.prices[i] - market[i]) / market[i],
+                    implied_vol,
+                    vol_gap: implied_vol - self.opt_data.volatility[i],
+                }
+            })
+            .collect())
+    }
+
+    /// # self.select
+    /// Copies the rows at `indices` into a new Options, carrying computed
+    /// prices, greeks and implied vols where present, and a clone of the model.
+    ///
+    /// # args:
+    /// * `indices` - Row indices to copy, in output order.
     ///
     /// # returns:
-    /// A flattened representation of the data in a Vec<\[String;16\]>
-    pub fn to_records(&self) -> Vec<[String; 16]> {
-        if self.prices.is_empty() | self.greeks.is_empty() {
-            panic!("Prices or Greeks of wrong length, or uninitialized.")
+    /// An [`Options`] holding the selected rows.
+    pub fn select(&self, indices: &[usize]) -> Options {
+        let mut opt = Options::new(self.opt_data.select(indices), self.model.clone_box())
+            .with_price_floor(self.floor_prices);
+        if !self.prices.is_empty() {
+            opt.prices = indices.iter().map(|&i| self.prices[i]).collect();
+        }
+        if !self.greeks.is_empty() {
+            opt.greeks = indices.iter().map(|&i| self.greeks[i]).collect();
+        }
+        if !self.implied_vol.is_empty() {
+            opt.implied_vol = indices.iter().map(|&i| self.implied_vol[i]).collect();
         }
-        let mut records = Vec::with_capacity(self.opt_data.tickers.len());
+        if !self.errors.is_empty() {
+            opt.errors = indices.iter().map(|&i| self.errors[i]).collect();
+        }
+        opt
+    }
+
+    /// # self.group_by
+    /// Partitions rows into groups by a key, see [`Options::select`] for
+    /// what is carried into each group. Row order within groups is kept.
+    ///
+    /// # args:
+    /// * `key` - Function computing the group key of a row from its index.
+    ///
+    /// # returns:
+    /// A map from key to the [`Options`] of the group.
+    pub fn group_by<K, F>(&self, key: F) -> HashMap<K, Options>
+    where
+        K: Eq + Hash,
+        F: Fn(&OptData, usize) -> K,
+    {
+        let mut groups: HashMap<K, Vec<usize>> = HashMap::new();
         for i in 0..self.opt_data.tickers.len() {
-            records.push([
-                self.opt_data.tickers[i].clone(),
-                self.opt_data.opt_types[i].to_string(),
-                self.opt_data.underlying[i].to_string(),
-                self.opt_data.strike[i].to_string(),
-                self.opt_data.settles[i].to_string(),
-                self.opt_data.maturities[i].to_string(),
-                self.opt_data.duration[i].to_string(),
-                self.opt_data.dividend[i].to_string(),
-                self.opt_data.rfr[i].to_string(),
-                self.opt_data.volatility[i].to_string(),
-                self.prices[i].to_string(),
-                self.greeks[i].delta.to_string(),
-                self.greeks[i].gamma.to_string(),
-                self.greeks[i].vega.to_string(),
-                self.greeks[i].theta.to_string(),
-                self.greeks[i].rho.to_string(),
-            ])
-        }
-        records
+            groups.entry(key(&self.opt_data, i)).or_default().push(i);
+        }
+        groups
+            .into_iter()
+            .map(|(k, indices)| (k, self.select(&indices)))
+            .collect()
     }
 
-    /// # self.write_csv
-    /// Writes flattened records out to csv
-    pub fn write_csv(&self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        let mut wtr = Writer::from_path(path)?;
-        // Column headers
-        let headers = [
-            "ticker",
-            "opt_type",
-            "underlying",
-            "strike",
-            "settle",
-            "maturity",
-            "duration",
-            "dividend",
-            "rfr",
-            "volatility",
-            "price",
-            "delta",
-            "gamma",
-            "vega",
-            "theta",
-            "rho",
-        ];
-        wtr.write_record(headers).expect("failed writing headers");
-
-        // Collects chunked options_old back into one file, see function comment
-        // Check Iterator implementation for what records contain.
-        for rec in self.to_records() {
-            wtr.write_record(rec)
-                .expect("Failed writing file while iterating options_old.");
+    /// # self.group_by_expiry
+    /// Partitions rows into per (ticker, maturity) sub-chains.
+    ///
+    /// # returns:
+    /// A map from (ticker, maturity) to the [`Options`] of the sub-chain.
+    pub fn group_by_expiry(&self) -> HashMap<(String, DateTime<Utc>), Options> {
+        self.group_by(|d, i| (d.tickers[i].clone(), d.maturities[i]))
+    }
+
+    /// # self.portfolio_greeks
+    /// Aggregates greeks of a position holding `quantities` of each row.
+    ///
+    /// # args:
+    /// * `quantities` - Signed quantity per row.
+    ///
+    /// # returns:
+    /// Quantity weighted sum of [`Greeks`], or a [`LengthError`] if greeks
+    /// are not computed or `quantities` does not have one entry per row.
+    pub fn portfolio_greeks(&self, quantities: &[f64]) -> Result<Greeks, LengthError> {
+        let greeks = self.computed_greeks()?;
+        LengthError::check("quantities", greeks.len(), quantities.len())?;
+        let mut net = Greeks::default();
+        for (g, q) in greeks.iter().zip(quantities.iter()) {
+            net.delta += q * g.delta;
+            net.gamma += q * g.gamma;
+            net.vega += q * g.vega;
+            net.theta += q * g.theta;
+            net.rho += q * g.rho;
+        }
+        Ok(net)
+    }
+
+    /// # self.hedge_two
+    /// Quantities of two rows that neutralize two greeks of a position,
+    /// see [`two_option_hedge`].
+    ///
+    /// # args:
+    /// * `quantities` - Signed quantity per row making up the position.
+    /// * `hedge` - Row indices of the two hedge options.
+    /// * `targets` - The two greeks to neutralize.
+    ///
+    /// # returns:
+    /// Quantities of the two hedge rows, `None` if the system is singular or
+    /// a hedge index is out of bounds, or the error of
+    /// [`Options::portfolio_greeks`].
+    pub fn hedge_two(
+        &self,
+        quantities: &[f64],
+        hedge: (usize, usize),
+        targets: (GreekKind, GreekKind),
+    ) -> Result<Option<(f64, f64)>, LengthError> {
+        let position = self.portfolio_greeks(quantities)?;
+        let (Some(first), Some(second)) = (self.greeks.get(hedge.0), self.greeks.get(hedge.1))
+        else {
+            return Ok(None);
+        };
+        Ok(two_option_hedge(&position, first, second, targets))
+    }
+
+    /// # self.expected_move
+    /// Implied one standard deviation move of the underlying until expiry from
+    /// the at the money straddle, per (ticker, maturity). The strike closest to
+    /// the underlying with both a call and a put listed is used. Since an ATM
+    /// straddle is worth about `sqrt(2 / pi)` standard deviations, the move is
+    /// the straddle price times `sqrt(pi / 2)`.
+    ///
+    /// # returns:
+    /// `(ticker, maturity, move)` sorted by ticker and maturity. Groups without
+    /// a call and put at a common strike are omitted. A [`LengthError`] if
+    /// prices are not computed.
+    pub fn expected_move(&self) -> Result<Vec<(String, DateTime<Utc>, f64)>, LengthError> {
+        self.computed_prices()?;
+        let mut moves: Vec<(String, DateTime<Utc>, f64)> = self
+            .group_by_expiry()
+            .into_iter()
+            .filter_map(|((ticker, maturity), group)| {
+                let d = &group.opt_data;
+                let spot = d.underlying[0];
+                let mut best: Option<(f64, f64)> = None; // (distance to spot, straddle price)
+                for c in 0..d.tickers.len() {
+                    if d.opt_types[c] != OptTypes::Call {
+                        continue;
+                    }
+                    let put = (0..d.tickers.len())
+                        .find(|&p| d.opt_types[p] == OptTypes::Put && d.strike[p] == d.strike[c]);
+                    if let Some(p) = put {
+                        let distance = (d.strike[c] - spot).abs();
+                        if best.is_none_or(|(b, _)| distance < b) {
+                            best = Some((distance, group.prices[c] + group.prices[p]));
+                        }
+                    }
+                }
+                best.map(|(_, straddle)| {
+                    (
+                        ticker,
+                        maturity,
+                        straddle * (std::f64::consts::PI / 2.0).sqrt(),
+                    )
+                })
+            })
+            .collect();
+        moves.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+        Ok(moves)
+    }
+
+    /// # self.atm_vol_and_skew
+    /// ATM implied vol and skew per (ticker, maturity) from the solved
+    /// implied vols. Strikes are placed at `x = ln(strike / forward)` with
+    /// the forward `underlying * exp((rfr - dividend) * duration)`, calls
+    /// and puts at the same strike are averaged. The two strikes closest to
+    /// the forward, one on each side where available, are interpolated
+    /// linearly: the ATM vol is the line at `x = 0` and the skew its slope.
+    ///
+    /// # returns:
+    /// An [`AtmSkew`] per expiry sorted by ticker and maturity. Rows with a
+    /// non-finite implied vol are skipped, expiries with fewer than two
+    /// usable strikes are omitted. A [`LengthError`] if implied vols are not
+    /// solved, see [`Options::solve_implied_vols`].
+    pub fn atm_vol_and_skew(&self) -> Result<Vec<AtmSkew>, LengthError> {
+        self.computed_implied_vols()?;
+        let mut surface: Vec<AtmSkew> = self
+            .group_by_expiry()
+            .into_iter()
+            .filter_map(|((ticker, maturity), group)| {
+                let d = &group.opt_data;
+                // (log moneyness, sum of vols, count) per strike
+                let mut strikes: Vec<(f64, f64, f64)> = Vec::new();
+                for i in 0..d.tickers.len() {
+                    let iv = group.implied_vol[i];
+                    let forward =
+                        d.underlying[i] * ((d.rfr[i] - d.dividend[i]) * d.duration[i]).exp();
+                    let x = (d.strike[i] / forward).ln();
+                    if !iv.is_finite() || !x.is_finite() {
+                        continue;
+                    }
+                    match strikes.iter_mut().find(|(sx, _, _)| *sx == x) {
+                        Some((_, sum, n)) => {
+                            *sum += iv;
+                            *n += 1.0;
+                        }
+                        None => strikes.push((x, iv, 1.0)),
+                    }
+                }
+                let mut points: Vec<(f64, f64)> = strikes
+                    .into_iter()
+                    .map(|(x, sum, n)| (x, sum / n))
+                    .collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                if points.len() < 2 {
+                    return None;
+                }
+                // First strike above the forward, clamped so both ends exist
+                let upper = points
+                    .iter()
+                    .position(|(x, _)| *x > 0.0)
+                    .unwrap_or(points.len() - 1)
+                    .max(1);
+                let ((x0, v0), (x1, v1)) = (points[upper - 1], points[upper]);
+                let skew = (v1 - v0) / (x1 - x0);
+                Some(AtmSkew {
+                    ticker,
+                    maturity,
+                    atm_vol: v0 - skew * x0,
+                    skew,
+                })
+            })
+            .collect();
+        surface.sort_by(|a, b| (&a.ticker, a.maturity).cmp(&(&b.ticker, b.maturity)));
+        Ok(surface)
+    }
+
+    /// # self.premiums
+    /// Premium per contract, the price scaled by the contract multiplier.
+    ///
+    /// # returns:
+    /// A vector of premiums in each option's currency, or a [`LengthError`]
+    /// if prices are not computed.
+    pub fn premiums(&self) -> Result<Vec<f64>, LengthError> {
+        Ok(self
+            .computed_prices()?
+            .iter()
+            .zip(self.opt_data.multiplier.iter())
+            .map(|(price, multiplier)| price * multiplier)
+            .collect())
+    }
+
+    /// # self.edge
+    /// Compares model prices to the quoted mid, see [`OptData::with_quotes`].
+    ///
+    /// # returns:
+    /// An [`Edge`] per row, `None` for rows missing a bid or an ask, or a
+    /// [`LengthError`] if prices are not computed.
+    pub fn edge(&self) -> Result<Vec<Option<Edge>>, LengthError> {
+        Ok(self
+            .computed_prices()?
+            .iter()
+            .zip(self.opt_data.bid.iter().zip(self.opt_data.ask.iter()))
+            .map(|(&price, quote)| match quote {
+                (Some(bid), Some(ask)) => {
+                    let mid = (bid + ask) / 2.0;
+                    Some(Edge {
+                        mid,
+                        edge: price - mid,
+                        outside_spread: price < *bid || price > *ask,
+                    })
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// # self.moneyness_class
+    /// Classifies every row as in, at or out of the money relative to spot.
+    ///
+    /// # args:
+    /// * `atm_band` - Relative distance of spot from strike counted as at the
+    ///   money, e.g. 0.02 for within 2% of the strike.
+    ///
+    /// # returns:
+    /// A [`Moneyness`] per row. Calls are in the money above the band, puts below.
+    pub fn moneyness_class(&self, atm_band: f64) -> Vec<Moneyness> {
+        let d = &self.opt_data;
+        (0..d.tickers.len())
+            .map(|i| {
+                let distance = d.underlying[i] / d.strike[i] - 1.0;
+                if distance.abs() <= atm_band {
+                    return Moneyness::AtTheMoney;
+                }
+                match (d.opt_types[i], distance > 0.0) {
+                    (OptTypes::Call, true) | (OptTypes::Put, false) => Moneyness::InTheMoney,
+                    (OptTypes::Call, false) | (OptTypes::Put, true) => Moneyness::OutOfTheMoney,
+                }
+            })
+            .collect()
+    }
+
+    /// # self.pin_risk
+    /// Rows carrying pin risk: close to expiry with spot close to the strike,
+    /// where delta and gamma become unstable and assignment is uncertain.
+    ///
+    /// # args:
+    /// * `spot_band` - Relative distance of spot from strike, e.g. 0.01 for
+    ///   within 1% of the strike, like `atm_band` of [`Options::moneyness_class`].
+    /// * `days_band` - Calendar days to expiry, measured on
+    ///   [`OptData::duration`] in years of [`DAYS_PER_YEAR`] days.
+    ///
+    /// # returns:
+    /// Indices of the flagged rows in ascending order.
+    pub fn pin_risk(&self, spot_band: f64, days_band: f64) -> Vec<usize> {
+        let d = &self.opt_data;
+        (0..d.tickers.len())
+            .filter(|&i| {
+                let days = d.duration[i] * DAYS_PER_YEAR;
+                let distance = d.underlying[i] / d.strike[i] - 1.0;
+                (0.0..=days_band).contains(&days) && distance.abs() <= spot_band
+            })
+            .collect()
+    }
+
+    /// # self.greek_buckets
+    /// Sums greeks into a grid of moneyness and tenor buckets for risk reports.
+    /// Moneyness is spot over strike and tenor the duration in years. With
+    /// `n` sorted edges there are `n + 1` buckets per axis, bucket `j` holds
+    /// values from edge `j - 1` inclusive up to edge `j` exclusive.
+    ///
+    /// # args:
+    /// * `moneyness_edges` - Ascending bucket edges of spot over strike.
+    /// * `tenor_edges` - Ascending bucket edges of the duration in years.
+    ///
+    /// # returns:
+    /// Summed [`Greeks`] by (moneyness bucket, tenor bucket), empty buckets
+    /// are left out. A [`LengthError`] if greeks are not computed.
+    pub fn greek_buckets(
+        &self,
+        moneyness_edges: &[f64],
+        tenor_edges: &[f64],
+    ) -> Result<BTreeMap<(usize, usize), Greeks>, LengthError> {
+        let d = &self.opt_data;
+        let mut buckets: BTreeMap<(usize, usize), Greeks> = BTreeMap::new();
+        for (i, greeks) in self.computed_greeks()?.iter().enumerate() {
+            let moneyness = d.underlying[i] / d.strike[i];
+            let bucket = (
+                moneyness_edges.partition_point(|edge| *edge <= moneyness),
+                tenor_edges.partition_point(|edge| *edge <= d.duration[i]),
+            );
+            *buckets.entry(bucket).or_default() += *greeks;
+        }
+        Ok(buckets)
+    }
+
+    /// # self.vega_ladder
+    /// Position weighted vega by tenor bucket, to hedge vega per expiry.
+    /// Buckets follow [`Options::greek_buckets`], with `n` sorted edges there
+    /// are `n + 1` buckets and bucket `j` holds durations from edge `j - 1`
+    /// inclusive up to edge `j` exclusive.
+    ///
+    /// # args:
+    /// * `tenor_edges` - Ascending bucket edges of the duration in years.
+    /// * `quantities` - Signed quantity per row.
+    ///
+    /// # returns:
+    /// Net vega by tenor bucket, empty buckets are left out.
+    /// [`OptionsError::Inconsistent`] if greeks are not computed or
+    /// `quantities` does not have one entry per row, and
+    /// [`OptionsError::InvalidArgument`] if `tenor_edges` is not ascending.
+    pub fn vega_ladder(
+        &self,
+        tenor_edges: &[f64],
+        quantities: &[f64],
+    ) -> Result<BTreeMap<usize, f64>, OptionsError> {
+        let greeks = self.computed_greeks()?;
+        LengthError::check("quantities", greeks.len(), quantities.len())?;
+        if !tenor_edges.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(OptionsError::InvalidArgument(format!(
+                "tenor_edges {:?} are not strictly ascending",
+                tenor_edges
+            )));
+        }
+        let mut ladder: BTreeMap<usize, f64> = BTreeMap::new();
+        for ((g, q), duration) in greeks
+            .iter()
+            .zip(quantities.iter())
+            .zip(self.opt_data.duration.iter())
+        {
+            let bucket = tenor_edges.partition_point(|edge| *edge <= *duration);
+            *ladder.entry(bucket).or_default() += q * g.vega;
+        }
+        Ok(ladder)
+    }
+
+    /// # self.vol_consistency
+    /// Flags rows whose input volatility diverges from the quoted implied vol,
+    /// e.g. stale inputs. Rows without a quote are skipped.
+    ///
+    /// # args:
+    /// * `tol` - Largest accepted absolute difference, e.g. 0.01 for a vol point.
+    ///
+    /// # returns:
+    /// Indices of the diverging rows, ordered by row.
+    pub fn vol_consistency(&self, tol: f64) -> Vec<usize> {
+        let d = &self.opt_data;
+        (0..d.tickers.len())
+            .filter(|&i| {
+                d.quoted_iv[i].is_some_and(|quoted| {
+                    let gap = (d.volatility[i] - quoted).abs();
+                    gap.is_nan() || gap > tol
+                })
+            })
+            .collect()
+    }
+
+    /// # self.representative_iv
+    /// One number volatility level per ticker, the vega weighted average of
+    /// the chain's volatilities. At the money options dominate the average,
+    /// far wings with little vega barely move it. A row's solved implied vol
+    /// is used where available, see [`Options::solve_implied_vols`], and its
+    /// input volatility otherwise.
+    ///
+    /// # returns:
+    /// `(ticker, vol)` sorted by ticker. Rows with a non-finite vol or vega
+    /// are skipped, tickers without positive total vega are omitted. A
+    /// [`LengthError`] if greeks are not computed.
+    pub fn representative_iv(&self) -> Result<Vec<(String, f64)>, LengthError> {
+        self.computed_greeks()?;
+        let d = &self.opt_data;
+        let mut sums: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+        for i in 0..d.tickers.len() {
+            let vol = match self.implied_vol.get(i) {
+                Some(iv) if iv.is_finite() => *iv,
+                _ => d.volatility[i],
+            };
+            let vega = self.greeks[i].vega;
+            if !vol.is_finite() || !vega.is_finite() {
+                continue;
+            }
+            let (weighted, total) = sums.entry(&d.tickers[i]).or_insert((0.0, 0.0));
+            *weighted += vega * vol;
+            *total += vega;
+        }
+        Ok(sums
+            .into_iter()
+            .filter(|(_, (_, total))| *total > 0.0)
+            .map(|(ticker, (weighted, total))| (ticker.to_string(), weighted / total))
+            .collect())
+    }
+
+    /// # self.net_premium
+    /// Net premium of a position holding `quantities` contracts of each row,
+    /// totalled per currency since premiums in different currencies do not add.
+    ///
+    /// # args:
+    /// * `quantities` - Signed number of contracts per row.
+    ///
+    /// # returns:
+    /// Sum of quantity times premium per contract by currency code, the error
+    /// of [`Options::premiums`], or a [`LengthError`] if `quantities` does not
+    /// have one entry per row.
+    pub fn net_premium(&self, quantities: &[f64]) -> Result<BTreeMap<String, f64>, LengthError> {
+        let premiums = self.premiums()?;
+        LengthError::check("quantities", premiums.len(), quantities.len())?;
+        let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+        for ((premium, quantity), currency) in premiums
+            .iter()
+            .zip(quantities.iter())
+            .zip(self.opt_data.currency.iter())
+        {
+            *totals.entry(currency.clone()).or_default() += premium * quantity;
+        }
+        Ok(totals)
+    }
+
+    /// # self.dollar_greeks
+    /// Greeks per contract, each greek scaled by the contract multiplier.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`] per contract, or a [`LengthError`] if greeks
+    /// are not computed.
+    pub fn dollar_greeks(&self) -> Result<Vec<Greeks>, LengthError> {
+        Ok(self
+            .computed_greeks()?
+            .iter()
+            .zip(self.opt_data.multiplier.iter())
+            .map(|(g, m)| Greeks {
+                delta: g.delta * m,
+                gamma: g.gamma * m,
+                vega: g.vega * m,
+                theta: g.theta * m,
+                rho: g.rho * m,
+            })
+            .collect())
+    }
+
+    /// # self.summary
+    /// Aggregates counts, price, volatility and delta statistics of the chain.
+    ///
+    /// # returns:
+    /// A [`ChainSummary`], printable with `{}`.
+    pub fn summary(&self) -> ChainSummary {
+        let calls = self
+            .opt_data
+            .opt_types
+            .iter()
+            .filter(|t| t.is_call())
+            .count();
+        let finite_prices = || self.prices.iter().copied().filter(|p| p.is_finite());
+        let deltas = || {
+            self.greeks
+                .iter()
+                .map(|g| g.delta)
+                .filter(|d| d.is_finite())
+        };
+        let non_finite = (0..self.opt_data.tickers.len())
+            .filter(|&i| {
+                self.prices.get(i).is_some_and(|p| !p.is_finite())
+                    || self.greeks.get(i).is_some_and(|g| {
+                        ![g.delta, g.gamma, g.vega, g.theta, g.rho]
+                            .iter()
+                            .all(|v| v.is_finite())
+                    })
+            })
+            .count();
+        ChainSummary {
+            count: self.opt_data.tickers.len(),
+            calls,
+            puts: self.opt_data.opt_types.len() - calls,
+            min_price: finite_prices().reduce(f64::min),
+            max_price: finite_prices().reduce(f64::max),
+            mean_price: mean(finite_prices()),
+            mean_volatility: mean(self.opt_data.volatility.iter().copied()),
+            mean_implied_vol: mean(self.implied_vol.iter().copied()),
+            total_delta: (!self.greeks.is_empty()).then(|| deltas().sum()),
+            mean_abs_delta: mean(deltas().map(f64::abs)),
+            non_finite,
+        }
+    }
+
+    /// # self.check_consistency
+    /// Checks that every input column has one entry per row, and that computed
+    /// prices, greeks, implied vols and errors are either empty or one per row.
+    ///
+    /// # returns:
+    /// A [`LengthError`] naming the first inconsistent vector.
+    pub fn check_consistency(&self) -> Result<(), LengthError> {
+        let d = &self.opt_data;
+        let n = d.tickers.len();
+        for (name, len) in [
+            ("opt_types", d.opt_types.len()),
+            ("underlying", d.underlying.len()),
+            ("strike", d.strike.len()),
+            ("settles", d.settles.len()),
+            ("maturities", d.maturities.len()),
+            ("duration", d.duration.len()),
+            ("dividend", d.dividend.len()),
+            ("rfr", d.rfr.len()),
+            ("volatility", d.volatility.len()),
+            ("multiplier", d.multiplier.len()),
+            ("currency", d.currency.len()),
+            ("market_price", d.market_price.len()),
+            ("bid", d.bid.len()),
+            ("ask", d.ask.len()),
+            ("exercise", d.exercise.len()),
+            ("fx", d.fx.len()),
+            ("quoted_iv", d.quoted_iv.len()),
+            ("source", d.source.len()),
+        ] {
+            LengthError::check(name, n, len)?;
+        }
+        for (name, len) in [
+            ("prices", self.prices.len()),
+            ("greeks", self.greeks.len()),
+            ("implied_vol", self.implied_vol.len()),
+            ("errors", self.errors.len()),
+        ] {
+            if len != 0 {
+                LengthError::check(name, n, len)?;
+            }
         }
         Ok(())
     }
+
+    /// # self.is_consistent
+    /// See [`Options::check_consistency`].
+    ///
+    /// # returns:
+    /// True if all vectors have consistent lengths.
+    pub fn is_consistent(&self) -> bool {
+        self.check_consistency().is_ok()
+    }
+
+    /// # self.computed_prices
+    /// Prices of every row, or a [`LengthError`] if they are not computed.
+    pub(crate) fn computed_prices(&self) -> Result<&[f64], LengthError> {
+        LengthError::check("prices", self.opt_data.tickers.len(), self.prices.len())?;
+        Ok(&self.prices)
+    }
+
+    /// # self.computed_greeks
+    /// Greeks of every row, or a [`LengthError`] if they are not computed.
+    pub(crate) fn computed_greeks(&self) -> Result<&[Greeks], LengthError> {
+        LengthError::check("greeks", self.opt_data.tickers.len(), self.greeks.len())?;
+        Ok(&self.greeks)
+    }
+
+    /// # self.computed_implied_vols
+    /// Implied vols of every row, or a [`LengthError`] if they are not solved.
+    pub(crate) fn computed_implied_vols(&self) -> Result<&[f64], LengthError> {
+        LengthError::check(
+            "implied_vol",
+            self.opt_data.tickers.len(),
+            self.implied_vol.len(),
+        )?;
+        Ok(&self.implied_vol)
+    }
+
+    /// # self.to_typed_records
+    /// Flattens option data into typed records, the columns of
+    /// [`RECORD_HEADERS`] without stringifying, for in-memory consumers.
+    ///
+    /// # returns:
+    /// An [`OptRecord`] per row, or a [`LengthError`] naming the first vector
+    /// that does not have one entry per row. Prices and greeks have to be computed.
+    pub fn to_typed_records(&self) -> Result<Vec<OptRecord>, LengthError> {
+        self.check_consistency()?;
+        let n = self.opt_data.tickers.len();
+        LengthError::check("prices", n, self.prices.len())?;
+        LengthError::check("greeks", n, self.greeks.len())?;
+        let d = &self.opt_data;
+        Ok((0..n)
+            .map(|i| OptRecord {
+                ticker: d.tickers[i].clone(),
+                opt_type: d.opt_types[i],
+                underlying: d.underlying[i],
+                strike: d.strike[i],
+                settle: d.settles[i],
+                maturity: d.maturities[i],
+                duration: d.duration[i],
+                dividend: d.dividend[i],
+                rfr: d.rfr[i],
+                volatility: d.volatility[i],
+                multiplier: d.multiplier[i],
+                currency: d.currency[i].clone(),
+                price: self.prices[i],
+                greeks: self.greeks[i],
+            })
+            .collect())
+    }
+
+    /// # self.to_records
+    /// Flattens option data (deserialize to vector of flat records)
+    ///
+    /// # returns:
+    /// A flattened representation of the data in a Vec<\[String;18\]>, or a
+    /// [`LengthError`] naming the first vector that does not have one entry
+    /// per row. Prices and greeks have to be computed. Dates are RFC3339 so
+    /// written files can be parsed again.
+    pub fn to_records(&self) -> Result<Vec<[String; 18]>, LengthError> {
+        Ok(self
+            .to_typed_records()?
+            .iter()
+            .map(OptRecord::to_strings)
+            .collect())
+    }
+
+    /// # self.greek_records
+    /// Flattens tickers and greeks, with the implied vol appended when solved.
+    ///
+    /// # returns:
+    /// Records with the [`Options::greek_headers`] columns, or a
+    /// [`LengthError`] as in [`Options::to_records`]. Greeks have to be computed.
+    pub fn greek_records(&self) -> Result<Vec<Vec<String>>, LengthError> {
+        self.check_consistency()?;
+        LengthError::check("greeks", self.opt_data.tickers.len(), self.greeks.len())?;
+        let with_iv = !self.implied_vol.is_empty();
+        Ok((0..self.opt_data.tickers.len())
+            .map(|i| {
+                let mut rec = vec![self.opt_data.tickers[i].clone()];
+                rec.extend(self.greeks[i].to_record());
+                if with_iv {
+                    rec.push(self.implied_vol[i].to_string());
+                }
+                rec
+            })
+            .collect())
+    }
+
+    /// # self.greek_headers
+    /// Column headers of [`Options::greek_records`].
+    pub fn greek_headers(&self) -> Vec<&'static str> {
+        let mut headers = vec!["ticker"];
+        headers.extend(GREEK_HEADERS);
+        if !self.implied_vol.is_empty() {
+            headers.push("implied_vol");
+        }
+        headers
+    }
+
+    /// # self.write_csv
+    /// Writes flattened records out to csv, see [`Options::write_csv_to`].
+    /// Inconsistent data is reported before the file is created.
+    pub fn write_csv(&self, path: PathBuf) -> Result<(), OptionsError> {
+        self.check_consistency()?;
+        self.write_csv_to(File::create(path)?)
+    }
+
+    /// # self.write_csv_to
+    /// Writes flattened records as csv with a [`RECORD_HEADERS`] header to any writer,
+    /// e.g. stdout or an in-memory buffer.
+    ///
+    /// # args:
+    /// * `w` - Destination of the csv.
+    ///
+    /// # returns:
+    /// An error if the data is inconsistent or writing fails.
+    pub fn write_csv_to<W: io::Write>(&self, w: W) -> Result<(), OptionsError> {
+        // Build records before writing so inconsistent data writes nothing
+        write_records(w, &RECORD_HEADERS, self.to_records()?)
+    }
+
+    /// # self.write_greeks_csv
+    /// Writes tickers and greeks out to csv, see [`Options::write_greeks_csv_to`].
+    /// Inconsistent data is reported before the file is created.
+    pub fn write_greeks_csv(&self, path: PathBuf) -> Result<(), OptionsError> {
+        self.greek_records()?;
+        self.write_greeks_csv_to(File::create(path)?)
+    }
+
+    /// # self.write_greeks_csv_to
+    /// Writes [`Options::greek_records`] as csv to any writer.
+    ///
+    /// # args:
+    /// * `w` - Destination of the csv.
+    ///
+    /// # returns:
+    /// An error if greeks are not computed, the data is inconsistent or writing fails.
+    pub fn write_greeks_csv_to<W: io::Write>(&self, w: W) -> Result<(), OptionsError> {
+        write_records(w, &self.greek_headers(), self.greek_records()?)
+    }
+}
+
+/// # write_records
+/// Writes a header and records as csv and flushes the writer.
+fn write_records<W, R>(w: W, headers: &[&str], records: Vec<R>) -> Result<(), OptionsError>
+where
+    W: io::Write,
+    R: AsRef<[String]>,
+{
+    let mut wtr = Writer::from_writer(w);
+    wtr.write_record(headers)?;
+    for rec in records {
+        wtr.write_record(rec.as_ref())?;
+    }
+    wtr.flush()?;
+    Ok(())
 }
 
 impl Default for Options {
@@ -185,8 +1796,284 @@ impl Default for Options {
             opt_data: OptData::default(),
             prices: vec![],
             greeks: vec![],
+            implied_vol: vec![],
+            errors: vec![],
             model: Box::new(BlackScholesModel::new()),
-            iter_count: 0,
+            floor_prices: true,
+        }
+    }
+}
+
+/// # OptRecord
+/// Typed record of a priced row, the columns of [`RECORD_HEADERS`], see
+/// [`Options::to_typed_records`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptRecord {
+    pub ticker: String,
+    pub opt_type: OptTypes,
+    pub underlying: f64,
+    pub strike: f64,
+    pub settle: DateTime<Utc>,
+    pub maturity: DateTime<Utc>,
+    /// Duration in years.
+    pub duration: f64,
+    pub dividend: f64,
+    pub rfr: f64,
+    pub volatility: f64,
+    pub multiplier: f64,
+    pub currency: String,
+    pub price: f64,
+    pub greeks: Greeks,
+}
+
+impl OptRecord {
+    /// # self.to_strings
+    /// Stringifies the record in the order of [`RECORD_HEADERS`], dates as RFC3339.
+    pub fn to_strings(&self) -> [String; 18] {
+        [
+            self.ticker.clone(),
+            self.opt_type.to_string(),
+            self.underlying.to_string(),
+            self.strike.to_string(),
+            self.settle.to_rfc3339(),
+            self.maturity.to_rfc3339(),
+            self.duration.to_string(),
+            self.dividend.to_string(),
+            self.rfr.to_string(),
+            self.volatility.to_string(),
+            self.multiplier.to_string(),
+            self.currency.clone(),
+            self.price.to_string(),
+            self.greeks.delta.to_string(),
+            self.greeks.gamma.to_string(),
+            self.greeks.vega.to_string(),
+            self.greeks.theta.to_string(),
+            self.greeks.rho.to_string(),
+        ]
+    }
+}
+
+/// # OptRow
+/// Owned, typed row of an [`Options`], yielded by consuming it with `into_iter`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptRow {
+    pub ticker: String,
+    pub opt_type: OptTypes,
+    pub underlying: f64,
+    pub strike: f64,
+    pub settle: DateTime<Utc>,
+    pub maturity: DateTime<Utc>,
+    /// Duration in years.
+    pub duration: f64,
+    pub dividend: f64,
+    pub rfr: f64,
+    pub volatility: f64,
+    pub multiplier: f64,
+    pub currency: String,
+    /// `None` if prices were not computed.
+    pub price: Option<f64>,
+    /// `None` if greeks were not computed.
+    pub greeks: Option<Greeks>,
+    /// `None` if implied vols were not solved.
+    pub implied_vol: Option<f64>,
+}
+
+/// # OptionsIntoIter
+/// Consuming iterator over the rows of an [`Options`], moves the column
+/// vectors instead of cloning them.
+pub struct OptionsIntoIter {
+    tickers: std::vec::IntoIter<String>,
+    opt_types: std::vec::IntoIter<OptTypes>,
+    underlying: std::vec::IntoIter<f64>,
+    strike: std::vec::IntoIter<f64>,
+    settles: std::vec::IntoIter<DateTime<Utc>>,
+    maturities: std::vec::IntoIter<DateTime<Utc>>,
+    duration: std::vec::IntoIter<f64>,
+    dividend: std::vec::IntoIter<f64>,
+    rfr: std::vec::IntoIter<f64>,
+    volatility: std::vec::IntoIter<f64>,
+    multiplier: std::vec::IntoIter<f64>,
+    currency: std::vec::IntoIter<String>,
+    prices: std::vec::IntoIter<f64>,
+    greeks: std::vec::IntoIter<Greeks>,
+    implied_vol: std::vec::IntoIter<f64>,
+}
+
+impl Iterator for OptionsIntoIter {
+    type Item = OptRow;
+
+    fn next(&mut self) -> Option<OptRow> {
+        Some(OptRow {
+            ticker: self.tickers.next()?,
+            opt_type: self.opt_types.next()?,
+            underlying: self.underlying.next()?,
+            strike: self.strike.next()?,
+            settle: self.settles.next()?,
+            maturity: self.maturities.next()?,
+            duration: self.duration.next()?,
+            dividend: self.dividend.next()?,
+            rfr: self.rfr.next()?,
+            volatility: self.volatility.next()?,
+            multiplier: self.multiplier.next()?,
+            currency: self.currency.next()?,
+            // Results not computed are empty
+            price: self.prices.next(),
+            greeks: self.greeks.next(),
+            implied_vol: self.implied_vol.next(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tickers.size_hint()
+    }
+}
+
+impl IntoIterator for Options {
+    type Item = OptRow;
+    type IntoIter = OptionsIntoIter;
+
+    /// # self.into_iter
+    /// Consumes the options into owned [`OptRow`]s, e.g. `for row in opts`.
+    fn into_iter(self) -> OptionsIntoIter {
+        let d = self.opt_data;
+        OptionsIntoIter {
+            tickers: d.tickers.into_iter(),
+            opt_types: d.opt_types.into_iter(),
+            underlying: d.underlying.into_iter(),
+            strike: d.strike.into_iter(),
+            settles: d.settles.into_iter(),
+            maturities: d.maturities.into_iter(),
+            duration: d.duration.into_iter(),
+            dividend: d.dividend.into_iter(),
+            rfr: d.rfr.into_iter(),
+            volatility: d.volatility.into_iter(),
+            multiplier: d.multiplier.into_iter(),
+            currency: d.currency.into_iter(),
+            prices: self.prices.into_iter(),
+            greeks: self.greeks.into_iter(),
+            implied_vol: self.implied_vol.into_iter(),
+        }
+    }
+}
+
+/// # ContractKey
+/// Identifies a contract across chains by ticker, option type, strike and
+/// maturity, like [`OptData::find_duplicates`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractKey {
+    pub ticker: String,
+    pub opt_type: OptTypes,
+    pub strike: f64,
+    pub maturity: DateTime<Utc>,
+}
+
+impl ContractKey {
+    /// # ContractKey::of
+    /// Key of row `i` of `opt_data`.
+    pub fn of(opt_data: &OptData, i: usize) -> Self {
+        ContractKey {
+            ticker: opt_data.tickers[i].clone(),
+            opt_type: opt_data.opt_types[i],
+            strike: opt_data.strike[i],
+            maturity: opt_data.maturities[i],
+        }
+    }
+}
+
+/// # ChainDiff
+/// Difference of a contract between two priced chains, see [`diff_chains`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainDiff {
+    /// The contract is in both chains. Differences are `b - a`, greeks are
+    /// `None` unless computed in both chains.
+    Matched {
+        key: ContractKey,
+        row_a: usize,
+        row_b: usize,
+        price: f64,
+        greeks: Option<Greeks>,
+    },
+    /// The contract is only in the first chain.
+    OnlyInA { key: ContractKey, row: usize },
+    /// The contract is only in the second chain.
+    OnlyInB { key: ContractKey, row: usize },
+}
+
+/// # diff_chains
+/// Compares two priced chains, e.g. before and after changing an input.
+/// Rows are matched by [`ContractKey`] rather than position, so reordered
+/// chains compare equal. Repeated keys are matched in order of occurrence.
+///
+/// # args:
+/// * `a` - The reference chain.
+/// * `b` - The chain to compare against it.
+///
+/// # returns:
+/// Matched rows and rows only in `a` in the row order of `a`, followed by
+/// rows only in `b` in the row order of `b`. Matched rows are reported even
+/// if nothing moved, filter on the differences to keep the moves. A
+/// [`LengthError`] if prices are not computed in either chain, or
+/// [`OptionsError::InvalidArgument`] if a strike is NaN, which matches no
+/// contract. Strikes of `0.0` and `-0.0` match.
+pub fn diff_chains(a: &Options, b: &Options) -> Result<Vec<ChainDiff>, OptionsError> {
+    a.computed_prices()?;
+    b.computed_prices()?;
+    for (name, opt) in [("a", a), ("b", b)] {
+        if let Some(row) = opt.opt_data.strike.iter().position(|k| k.is_nan()) {
+            return Err(OptionsError::InvalidArgument(format!(
+                "strike of row {} in chain {} is NaN",
+                row, name
+            )));
+        }
+    }
+    // Adding 0.0 turns -0.0 into 0.0, so both hash alike
+    let hash_key = |d: &OptData, i: usize| {
+        (
+            d.tickers[i].clone(),
+            d.opt_types[i],
+            (d.strike[i] + 0.0).to_bits(),
+            d.maturities[i],
+        )
+    };
+    let mut unmatched_b: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for j in 0..b.opt_data.tickers.len() {
+        unmatched_b
+            .entry(hash_key(&b.opt_data, j))
+            .or_default()
+            .push_back(j);
+    }
+    let mut matched_b = vec![false; b.opt_data.tickers.len()];
+    let mut diffs = Vec::new();
+    for i in 0..a.opt_data.tickers.len() {
+        let key = ContractKey::of(&a.opt_data, i);
+        match unmatched_b
+            .get_mut(&hash_key(&a.opt_data, i))
+            .and_then(|rows| rows.pop_front())
+        {
+            Some(j) => {
+                matched_b[j] = true;
+                let greeks = match (a.greeks.get(i), b.greeks.get(j)) {
+                    (Some(ga), Some(gb)) => Some(*gb - *ga),
+                    _ => None,
+                };
+                diffs.push(ChainDiff::Matched {
+                    key,
+                    row_a: i,
+                    row_b: j,
+                    price: b.prices[j] - a.prices[i],
+                    greeks,
+                });
+            }
+            None => diffs.push(ChainDiff::OnlyInA { key, row: i }),
+        }
+    }
+    for (j, matched) in matched_b.into_iter().enumerate() {
+        if !matched {
+            diffs.push(ChainDiff::OnlyInB {
+                key: ContractKey::of(&b.opt_data, j),
+                row: j,
+            });
         }
     }
+    Ok(diffs)
 }
@@ -1,19 +1,29 @@
+use super::normal::{std_normal, StdNormal};
 use super::Options;
 use super::PricingModel;
-use crate::greeks::Greeks;
-use crate::options_struct::OptTypes;
-use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use crate::calendar::DayCount;
+use crate::greeks::{Compounding, Greeks};
+use crate::opt_data::DAYS_PER_YEAR;
+use crate::options_struct::{LengthError, OptTypes};
+use statrs::distribution::{Continuous, ContinuousCDF};
 
 /// # BlackScholesModel
 /// Model to compute prices and greeks. Uses extended
 /// Black-Scholes formula assuming continuous dividends.
 /// For a better view of the mathematics review the notebook <https://github.com/adaptive-alexander/portfolio/blob/main/options/docs/notes.ipynb>.
-pub struct BlackScholesModel;
-
-/// # Implement Send for BlackScholesModel
-/// Has to implement send to compute prices in parallel.
-/// Required by Options trait object bounds.
-unsafe impl Send for BlackScholesModel {}
+///
+/// With [`BlackScholesModel::with_underlying_is_forward`] the underlying is
+/// read as the forward price and no carry is applied, i.e. Black-76.
+///
+/// With [`BlackScholesModel::with_rate_day_count`] rates and dividends grow
+/// over a year fraction of their own, while volatility scales with the
+/// stored duration.
+#[derive(Clone, Copy, Debug)]
+pub struct BlackScholesModel {
+    underlying_is_forward: bool,
+    rho_compounding: Compounding,
+    rate_day_count: Option<DayCount>,
+}
 
 impl BlackScholesModel {
     /// # BlackScholesModel::new
@@ -22,12 +32,126 @@ impl BlackScholesModel {
     /// # returns:
     /// Returns a BlackScholesModel
     pub fn new() -> Self {
-        BlackScholesModel
+        BlackScholesModel {
+            underlying_is_forward: false,
+            rho_compounding: Compounding::Continuous,
+            rate_day_count: None,
+        }
+    }
+
+    /// # self.with_underlying_is_forward
+    /// Treats the underlying column as the forward price of each row. The
+    /// drift of d1 is skipped and prices are discounted forward payoffs,
+    /// dividends are ignored. Rho is the sensitivity at a fixed forward.
+    ///
+    /// # args:
+    /// * `underlying_is_forward` - Whether the underlying is the forward.
+    ///
+    /// # returns:
+    /// Returns the updated BlackScholesModel
+    pub fn with_underlying_is_forward(mut self, underlying_is_forward: bool) -> Self {
+        self.underlying_is_forward = underlying_is_forward;
+        self
+    }
+
+    /// # self.with_rho_compounding
+    /// Sets the rate convention rho is expressed in, e.g. to reconcile with
+    /// systems quoting annually compounded rates. Defaults to continuous.
+    /// Input rates and prices are unaffected.
+    ///
+    /// # args:
+    /// * `compounding` - See [`Compounding`].
+    ///
+    /// # returns:
+    /// Returns the updated BlackScholesModel
+    pub fn with_rho_compounding(mut self, compounding: Compounding) -> Self {
+        self.rho_compounding = compounding;
+        self
+    }
+
+    /// # self.with_rate_day_count
+    /// Discounts and accrues carry over the settle to maturity year fraction
+    /// in `day_count`, e.g. the basis the rate curve is quoted on, instead of
+    /// the stored duration. Volatility keeps scaling with the duration. By
+    /// default both use the duration.
+    ///
+    /// Rates enter the formulas only as `r * T`, so the chain is priced at
+    /// the duration with rates rescaled by the ratio of the two year
+    /// fractions. Rho is with respect to the quoted rate. Theta assumes both
+    /// year fractions shrink in proportion, exact for the fixed conventions.
+    ///
+    /// # args:
+    /// * `day_count` - Year fraction convention of the rates, see [`DayCount`].
+    ///
+    /// # returns:
+    /// Returns the updated BlackScholesModel
+    pub fn with_rate_day_count(mut self, day_count: DayCount) -> Self {
+        self.rate_day_count = Some(day_count);
+        self
+    }
+
+    /// # self.rate_adjusted
+    /// With a rate day count, the chain with rates and dividends rescaled to
+    /// the duration basis and a model pricing it without the day count, see
+    /// [`BlackScholesModel::with_rate_day_count`].
+    ///
+    /// # returns:
+    /// The model, the rescaled chain and the ratio of the rate year fraction
+    /// to the duration per row, or `None` without a rate day count.
+    fn rate_adjusted(&self, opts: &Options) -> Option<(BlackScholesModel, Options, Vec<f64>)> {
+        let day_count = self.rate_day_count?;
+        let mut data = opts.opt_data.clone();
+        let scales: Vec<f64> = (0..data.tickers.len())
+            .map(|i| {
+                let rate_duration = day_count.year_fraction(data.settles[i], data.maturities[i]);
+                if data.duration[i] > 0.0 {
+                    rate_duration / data.duration[i]
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+        for (i, scale) in scales.iter().enumerate() {
+            data.rfr[i] *= scale;
+            data.dividend[i] *= scale;
+        }
+        let inner = BlackScholesModel {
+            rho_compounding: Compounding::Continuous,
+            rate_day_count: None,
+            ..*self
+        };
+        Some((inner, Options::new(data, Box::new(inner)), scales))
+    }
+
+    /// # self.dividends
+    /// Dividend yields used for pricing. A forward carries at the risk free
+    /// rate, so `q = r` reduces the formulas to Black-76.
+    fn dividends<'a>(&self, opts: &'a Options) -> &'a [f64] {
+        if self.underlying_is_forward {
+            &opts.opt_data.rfr
+        } else {
+            &opts.opt_data.dividend
+        }
     }
 
     /// # self.get_d1
     /// Computes the parameter d1
     ///
+    /// At zero volatility or zero duration d1 is infinite with the sign of the
+    /// forward moneyness, so prices reduce to the discounted intrinsic value
+    /// of the forward. Rows exactly at the money forward are treated as out of
+    /// the money calls, worth their intrinsic value of zero.
+    ///
+    /// Greeks are the derivatives of that deterministic price rather than a
+    /// flat delta of ±1 with everything else zero. An in the money row has
+    /// delta ±e^(-qT), the delta of the prepaid forward, gamma and vega zero,
+    /// and theta and rho from the dividend carry and strike discounting, so
+    /// they agree with repricing the row at a bumped duration or rate. Out of
+    /// the money rows have all greeks zero.
+    ///
+    /// A zero strike is always exercised, d1 is infinite so a call is worth
+    /// the prepaid forward `S e^(-qT)` and a put nothing.
+    ///
     /// # returns:
     /// An f64 value for d1
     fn get_d1(
@@ -39,6 +163,17 @@ impl BlackScholesModel {
         volatility: &f64,
         duration: &f64,
     ) -> f64 {
+        if *strike == 0.0 {
+            return f64::INFINITY;
+        }
+        if *volatility == 0.0 || *duration == 0.0 {
+            let forward_moneyness = (underlying / strike).ln() + duration * (rfr - dividend);
+            return if forward_moneyness > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            };
+        }
         (1.0 / (volatility * duration.sqrt()))
             * ((underlying / strike).ln()
                 + duration * (rfr - dividend + (volatility.powf(2.0) / 2.0)))
@@ -52,11 +187,431 @@ impl BlackScholesModel {
     fn get_d2(&self, d1: &f64, volatility: &f64, duration: &f64) -> f64 {
         d1 - volatility * duration.sqrt()
     }
+
+    /// # self.vegas
+    /// Computes only the vega of every option, skipping the other greeks.
+    /// Intended for calibration loops that need vega at every step.
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of vegas, per 1% change in volatility like [`Greeks`] vega.
+    pub fn vegas(&self, opts: &Options) -> Vec<f64> {
+        if let Some((inner, adjusted, _)) = self.rate_adjusted(opts) {
+            return inner.vegas(&adjusted);
+        }
+        let dividend = self.dividends(opts);
+        let n = StdNormal::new();
+        (0..opts.opt_data.tickers.len())
+            .map(|i| {
+                let d1 = self.get_d1(
+                    &opts.opt_data.underlying[i],
+                    &opts.opt_data.strike[i],
+                    &dividend[i],
+                    &opts.opt_data.rfr[i],
+                    &opts.opt_data.volatility[i],
+                    &opts.opt_data.duration[i],
+                );
+                get_vega(
+                    &n,
+                    &d1,
+                    &opts.opt_data.underlying[i],
+                    &dividend[i],
+                    &opts.opt_data.duration[i],
+                )
+            })
+            .collect()
+    }
+
+    /// # self.spot_repricer
+    /// Precomputes the inputs of every row that do not depend on the underlying.
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A [`SpotRepricer`] pricing the rows at new underlyings.
+    pub fn spot_repricer(&self, opts: &Options) -> SpotRepricer {
+        if let Some((inner, adjusted, _)) = self.rate_adjusted(opts) {
+            return inner.spot_repricer(&adjusted);
+        }
+        SpotRepricer {
+            rows: (0..opts.opt_data.tickers.len())
+                .map(|i| self.spot_row(opts, i))
+                .collect(),
+        }
+    }
+
+    /// # self.rate_repricer
+    /// Precomputes d1, d2 and the discount factors of every row at the stored
+    /// rates, so parallel rate shifts only move the drift and discount terms.
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A [`RateRepricer`] pricing the rows at shifted rates.
+    pub fn rate_repricer(&self, opts: &Options) -> RateRepricer {
+        if let Some((inner, adjusted, scales)) = self.rate_adjusted(opts) {
+            let mut repricer = inner.rate_repricer(&adjusted);
+            for (row, scale) in repricer.rows.iter_mut().zip(scales) {
+                row.rate_time *= scale;
+            }
+            return repricer;
+        }
+        let d = &opts.opt_data;
+        let dividend = self.dividends(opts);
+        RateRepricer {
+            rows: (0..d.tickers.len())
+                .map(|i| {
+                    let (vol, t) = (d.volatility[i], d.duration[i]);
+                    let sd = vol * t.sqrt();
+                    let moneyness = if d.strike[i] == 0.0 {
+                        f64::INFINITY
+                    } else {
+                        (d.underlying[i] / d.strike[i]).ln()
+                            + t * (d.rfr[i] - dividend[i] + vol.powf(2.0) / 2.0)
+                    };
+                    RateRow {
+                        opt_type: d.opt_types[i],
+                        prepaid: d.underlying[i] * (-dividend[i] * t).exp(),
+                        discounted_strike: d.strike[i] * (-d.rfr[i] * t).exp(),
+                        moneyness,
+                        sd,
+                        rate_time: t,
+                        forward: self.underlying_is_forward,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// # self.price_curve
+    /// Prices a single row across a sweep of underlyings, keeping every other
+    /// input of the row fixed. Useful for plotting price curves.
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    /// * `row` - Row index, panics if out of bounds.
+    /// * `underlyings` - Underlyings to price the row at.
+    ///
+    /// # returns:
+    /// A price per underlying.
+    pub fn price_curve(&self, opts: &Options, row: usize, underlyings: &[f64]) -> Vec<f64> {
+        if let Some((inner, adjusted, _)) = self.rate_adjusted(opts) {
+            return inner.price_curve(&adjusted, row, underlyings);
+        }
+        let spot_row = self.spot_row(opts, row);
+        let n = StdNormal::new();
+        underlyings
+            .iter()
+            .map(|&spot| spot_row.price(&n, spot))
+            .collect()
+    }
+
+    /// # self.spot_row
+    /// Precomputes the spot independent constants of row `i`.
+    fn spot_row(&self, opts: &Options, i: usize) -> SpotRow {
+        let d = &opts.opt_data;
+        let (q, r, vol, t) = (
+            self.dividends(opts)[i],
+            d.rfr[i],
+            d.volatility[i],
+            d.duration[i],
+        );
+        SpotRow {
+            opt_type: d.opt_types[i],
+            strike: d.strike[i],
+            dividend_df: (-q * t).exp(),
+            rate_df: (-r * t).exp(),
+            shift: (r - q + vol.powf(2.0) / 2.0) * t - d.strike[i].ln(),
+            sd: vol * t.sqrt(),
+        }
+    }
+}
+
+/// # SpotRepricer
+/// Black-Scholes prices of a chain with every input except the underlying
+/// fixed. Discount factors, log strikes and drift terms are computed once in
+/// [`BlackScholesModel::spot_repricer`], so a spot update only evaluates the
+/// moneyness dependent part of every row.
+#[derive(Clone, Debug)]
+pub struct SpotRepricer {
+    rows: Vec<SpotRow>,
+}
+
+/// # SpotRow
+/// Constants of a single row of a [`SpotRepricer`].
+#[derive(Clone, Copy, Debug)]
+struct SpotRow {
+    opt_type: OptTypes,
+    strike: f64,
+    /// `e^(-qT)` and `e^(-rT)`.
+    dividend_df: f64,
+    rate_df: f64,
+    /// `(r - q + σ²/2)T - ln K`, d1 is `(ln S + shift) / sd`.
+    shift: f64,
+    /// `σ√T`.
+    sd: f64,
+}
+
+impl SpotRow {
+    /// # self.price
+    /// Price of the row at underlying `spot`.
+    fn price(&self, n: &StdNormal, spot: f64) -> f64 {
+        let moneyness = spot.ln() + self.shift;
+        let d1 = if self.sd == 0.0 {
+            // Deterministic payoff, see get_d1
+            if moneyness > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            }
+        } else {
+            moneyness / self.sd
+        };
+        let d2 = d1 - self.sd;
+        let w = self.opt_type.sign();
+        w * (spot * self.dividend_df * n.cdf(w * d1) - self.strike * self.rate_df * n.cdf(w * d2))
+    }
+}
+
+impl SpotRepricer {
+    /// # self.prices
+    /// Reprices every row at a new underlying.
+    ///
+    /// # args:
+    /// * `underlying` - New underlying of every row, one per row.
+    ///
+    /// # returns:
+    /// A vector of prices, or a [`LengthError`] if `underlying` does not have
+    /// one entry per row.
+    pub fn prices(&self, underlying: &[f64]) -> Result<Vec<f64>, LengthError> {
+        LengthError::check("underlying", self.rows.len(), underlying.len())?;
+        let n = StdNormal::new();
+        Ok(self
+            .rows
+            .iter()
+            .zip(underlying.iter())
+            .map(|(row, &spot)| row.price(&n, spot))
+            .collect())
+    }
+
+    /// # self.len
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// # self.is_empty
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// # RateRepricer
+/// Black-Scholes prices of a chain under parallel shifts of the risk free
+/// rate. The rate only enters through the drift of d1 and the discount
+/// factors, so [`BlackScholesModel::rate_repricer`] computes d1 and both
+/// discount factors once and a shift `dr` moves d1 by `dr T / σ√T` and
+/// scales the discount by `e^(-dr T)`.
+#[derive(Clone, Debug)]
+pub struct RateRepricer {
+    rows: Vec<RateRow>,
+}
+
+/// # RateRow
+/// Constants of a single row of a [`RateRepricer`].
+#[derive(Clone, Copy, Debug)]
+struct RateRow {
+    opt_type: OptTypes,
+    /// `S e^(-qT)` and `K e^(-rT)` at the stored rates.
+    prepaid: f64,
+    discounted_strike: f64,
+    /// `ln(S / K) + (r - q + σ²/2)T`, d1 is `moneyness / sd`.
+    moneyness: f64,
+    /// `σ√T`.
+    sd: f64,
+    /// Year fraction the rates accrue over, see [`BlackScholesModel::with_rate_day_count`].
+    rate_time: f64,
+    /// Whether the underlying is the forward, which carries at the shifted
+    /// rate so only the discounting moves.
+    forward: bool,
+}
+
+impl RateRow {
+    /// # self.price
+    /// Price of the row at the stored rate shifted by `rate_shift`.
+    fn price(&self, n: &StdNormal, rate_shift: f64) -> f64 {
+        let growth = (-rate_shift * self.rate_time).exp();
+        let (moneyness, prepaid) = if self.forward {
+            (self.moneyness, self.prepaid * growth)
+        } else {
+            (self.moneyness + rate_shift * self.rate_time, self.prepaid)
+        };
+        let d1 = if self.sd == 0.0 {
+            // Deterministic payoff, see get_d1
+            if moneyness > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            }
+        } else {
+            moneyness / self.sd
+        };
+        let d2 = d1 - self.sd;
+        let w = self.opt_type.sign();
+        w * (prepaid * n.cdf(w * d1) - self.discounted_strike * growth * n.cdf(w * d2))
+    }
+}
+
+impl RateRepricer {
+    /// # self.prices
+    /// Reprices every row at a parallel shift of the risk free rate.
+    ///
+    /// # args:
+    /// * `rate_shift` - Rate shift as a decimal, e.g. 0.0025 for 25bp.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    pub fn prices(&self, rate_shift: f64) -> Vec<f64> {
+        let n = StdNormal::new();
+        self.rows
+            .iter()
+            .map(|row| row.price(&n, rate_shift))
+            .collect()
+    }
+
+    /// # self.len
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// # self.is_empty
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// # get_vega
+/// Internal function used by get_greeks and vegas to compute option vega
+///
+/// # args:
+/// * `n` - A [`StdNormal`] used to calculate normal distributions.
+/// * `d1` - d1 from [`get_d1`].
+/// * `dividend` - Option dividend, assumed to be continuous.
+/// * `duration` - Duration of options_old contract in years.
+/// * `underlying` - Underlying price.
+///
+/// # returns:
+/// Option vega (sensitivity to volatility)
+fn get_vega(n: &StdNormal, d1: &f64, underlying: &f64, dividend: &f64, duration: &f64) -> f64 {
+    (1.0 / 100.0) * underlying * (-(dividend * duration)).exp() * duration.sqrt() * n.pdf(*d1)
+}
+
+/// # vol_for_delta
+/// Solves the Black-Scholes volatility at which an option has `target_delta`,
+/// the inverse of delta in volatility at a fixed strike and expiry.
+///
+/// d1 is `a / vol + b * vol` with `a` the log forward moneyness over the root
+/// of the duration and `b` half that root, so the volatility solves a quadratic.
+/// Forward out of the money d1 is increasing in volatility and every delta
+/// between 0 and the discounted bound is reached once. Forward in the money
+/// d1 has a minimum, deltas below it are unreachable and the others are reached
+/// at two volatilities, the lower one is returned.
+///
+/// # args:
+/// * `target_delta` - Delta to reproduce, positive for calls, negative for puts.
+/// * `opt_type` - Options type.
+/// * `underlying` - Underlying price.
+/// * `strike` - Strike price.
+/// * `duration` - Duration in years.
+/// * `rfr` - Risk free rate.
+/// * `dividend` - Continuous dividend yield.
+///
+/// # returns:
+/// The volatility, or `None` if no volatility gives `target_delta`.
+pub fn vol_for_delta(
+    target_delta: f64,
+    opt_type: OptTypes,
+    underlying: f64,
+    strike: f64,
+    duration: f64,
+    rfr: f64,
+    dividend: f64,
+) -> Option<f64> {
+    if duration.is_nan() || duration <= 0.0 {
+        return None;
+    }
+    let undiscounted = target_delta * (dividend * duration).exp();
+    let cdf_d1 = match opt_type {
+        OptTypes::Call => undiscounted,
+        OptTypes::Put => undiscounted + 1.0,
+    };
+    if !(cdf_d1 > 0.0 && cdf_d1 < 1.0) {
+        return None;
+    }
+    let d1 = std_normal().inverse_cdf(cdf_d1);
+    let a = ((underlying / strike).ln() + (rfr - dividend) * duration) / duration.sqrt();
+    let b = duration.sqrt() / 2.0;
+    let discriminant = d1 * d1 - 4.0 * a * b;
+    if discriminant < 0.0 {
+        return None;
+    }
+    // Roots of b * vol^2 - d1 * vol + a = 0
+    let (low, high) = (
+        (d1 - discriminant.sqrt()) / (2.0 * b),
+        (d1 + discriminant.sqrt()) / (2.0 * b),
+    );
+    [low, high].into_iter().find(|vol| *vol > 0.0)
+}
+
+/// # terminal_density
+/// Risk-neutral lognormal density of the underlying at maturity assumed by
+/// Black-Scholes, e.g. to plot the implied distribution or integrate custom
+/// payoffs over a grid. `ln S_T` is normal with mean `ln S + (r - q - σ²/2)T`
+/// and variance `σ²T`.
+///
+/// # args:
+/// * `underlying` - Underlying price today.
+/// * `grid` - Terminal prices to evaluate the density at, e.g. a strike grid.
+/// * `duration` - Duration in years.
+/// * `rfr` - Risk free rate.
+/// * `dividend` - Continuous dividend yield.
+/// * `volatility` - Annualized volatility.
+///
+/// # returns:
+/// The density at each grid point, 0.0 at non-positive prices. Without
+/// volatility or time the distribution is a point mass and the density is
+/// 0.0 everywhere.
+pub fn terminal_density(
+    underlying: f64,
+    grid: &[f64],
+    duration: f64,
+    rfr: f64,
+    dividend: f64,
+    volatility: f64,
+) -> Vec<f64> {
+    let sd = volatility * duration.sqrt();
+    let mean = underlying.ln() + (rfr - dividend - volatility.powf(2.0) / 2.0) * duration;
+    grid.iter()
+        .map(|&price| {
+            if price <= 0.0 || sd == 0.0 {
+                0.0
+            } else {
+                std_normal().pdf((price.ln() - mean) / sd) / (price * sd)
+            }
+        })
+        .collect()
 }
 
 impl Default for BlackScholesModel {
     fn default() -> Self {
-        BlackScholesModel
+        BlackScholesModel::new()
     }
 }
 
@@ -66,13 +621,29 @@ impl PricingModel for BlackScholesModel {
     ///
     /// # args:
     /// * `opts` - Takes a reference to options_old to use for calculations. This is passed self
-    /// from [`Options`] get_price function.
+    ///   from [`Options`] get_price function.
     ///
     /// # returns:
     /// A vector of prices.
+    /// # self.rate_shift_prices
+    /// Prices per rate shift through a [`RateRepricer`].
+    fn rate_shift_prices(&self, opts: &Options, rate_shifts: &[f64]) -> Option<Vec<Vec<f64>>> {
+        let repricer = self.rate_repricer(opts);
+        Some(
+            rate_shifts
+                .iter()
+                .map(|&shift| repricer.prices(shift))
+                .collect(),
+        )
+    }
+
     fn get_price(&self, opt: &Options) -> Vec<f64> {
+        if let Some((inner, adjusted, _)) = self.rate_adjusted(opt) {
+            return inner.get_price(&adjusted);
+        }
         // Initialize Standard Normal struct used to calculate distributions
-        let n = Normal::new(0.0, 1.0).unwrap();
+        let n = StdNormal::new();
+        let dividend = self.dividends(opt);
 
         // Initialize d1 and d2
         let mut d1: Vec<f64> = Vec::with_capacity(opt.opt_data.tickers.len());
@@ -86,7 +657,7 @@ impl PricingModel for BlackScholesModel {
             d1.push(self.get_d1(
                 &opt.opt_data.underlying[i],
                 &opt.opt_data.strike[i],
-                &opt.opt_data.dividend[i],
+                &dividend[i],
                 &opt.opt_data.rfr[i],
                 &opt.opt_data.volatility[i],
                 &opt.opt_data.duration[i],
@@ -97,29 +668,15 @@ impl PricingModel for BlackScholesModel {
                 &opt.opt_data.duration[i],
             ));
 
-            // Push price into return Vec
+            // Push price into return Vec, +1 for calls and -1 for puts
+            let w = opt.opt_data.opt_types[i].sign();
             prices.push(
-                // Compute price if call
-                if opt.opt_data.opt_types[i] == OptTypes::Call {
-                    opt.opt_data.underlying[i]
-                        * (-opt.opt_data.dividend[i] * opt.opt_data.duration[i]).exp()
-                        * n.cdf(d1[i])
-                        - opt.opt_data.strike[i]
-                            * (-opt.opt_data.rfr[i] * opt.opt_data.duration[i]).exp()
-                            * n.cdf(d2[i])
-                }
-                // Compute price if put
-                else if opt.opt_data.opt_types[i] == OptTypes::Put {
-                    opt.opt_data.strike[i]
+                w * (opt.opt_data.underlying[i]
+                    * (-dividend[i] * opt.opt_data.duration[i]).exp()
+                    * n.cdf(w * d1[i])
+                    - opt.opt_data.strike[i]
                         * (-opt.opt_data.rfr[i] * opt.opt_data.duration[i]).exp()
-                        * n.cdf(-d2[i])
-                        - opt.opt_data.underlying[i]
-                            * (-opt.opt_data.dividend[i] * opt.opt_data.duration[i]).exp()
-                            * n.cdf(-d1[i])
-                } else {
-                    // Enum only has above variants and is thus exhaustive.
-                    panic!("This should not be able to happen, option type outside enum.")
-                },
+                        * n.cdf(w * d2[i])),
             )
         }
         prices
@@ -130,7 +687,7 @@ impl PricingModel for BlackScholesModel {
     ///
     /// # args:
     /// * `opts` - Takes a reference to options_old to use for calculations. This is passed self
-    /// from [`Options`] get_price function.
+    ///   from [`Options`] get_price function.
     ///
     /// # returns:
     /// A vector of [`Greeks`].
@@ -139,7 +696,7 @@ impl PricingModel for BlackScholesModel {
         /// Internal function used by get_greeks to compute option delta
         ///
         /// # args:
-        /// *`n` - A [`Normal`] struct from statrs. Used to calculate normal distributions.
+        /// *`n` - A [`StdNormal`] used to calculate normal distributions.
         /// *`opt_type` - Options type.
         /// *`d1` - d1 from [`get_d1`]
         /// *`dividend` - Option dividend, assumed to be continuous.
@@ -148,26 +705,21 @@ impl PricingModel for BlackScholesModel {
         /// # returns:
         /// Option delta (sensitivity to price changes)
         fn get_delta(
-            n: &Normal,
+            n: &StdNormal,
             opt_type: &OptTypes,
             d1: &f64,
             dividend: &f64,
             duration: &f64,
         ) -> f64 {
-            let mut ret = 0.0;
-            if *opt_type == OptTypes::Call {
-                ret = (-(dividend * duration)).exp() * n.cdf(*d1)
-            } else if *opt_type == OptTypes::Put {
-                ret = (-(dividend * duration)).exp() * (n.cdf(*d1) - 1.0)
-            }
-            ret
+            let w = opt_type.sign();
+            w * (-(dividend * duration)).exp() * n.cdf(w * d1)
         }
 
         /// # get_gamma
         /// Internal function used by get_greeks to compute option gamma
         ///
         /// # args:
-        /// * `n` - A [`Normal`] struct from statrs. Used to calculate normal distributions.
+        /// * `n` - A [`StdNormal`] used to calculate normal distributions.
         /// * `d1` - d1 from [`get_d1`]
         /// * `dividend` - Option dividend, assumed to be continuous.
         /// * `duration` - Duration of options_old contract in years.
@@ -177,42 +729,26 @@ impl PricingModel for BlackScholesModel {
         /// # returns:
         /// Option gamma (sensitivity of delta changes f'(delta))
         fn get_gamma(
-            n: &Normal,
+            n: &StdNormal,
             d1: &f64,
             dividend: &f64,
             duration: &f64,
             underlying: &f64,
             volatility: &f64,
         ) -> f64 {
+            // Deterministic payoff, avoids 0 * inf
+            if *volatility == 0.0 || *duration == 0.0 {
+                return 0.0;
+            }
             (((-(dividend * duration)).exp()) / (underlying * volatility * duration.sqrt()))
                 * n.pdf(*d1)
         }
 
-        /// # get_vega
-        /// Internal function used by get_greeks to compute option vega
-        ///
-        /// # args:
-        /// * `n` - A [`Normal`] struct from statrs. Used to calculate normal distributions.
-        /// * `d1` - d1 from [`get_d1`].
-        /// * `dividend` - Option dividend, assumed to be continuous.
-        /// * `duration` - Duration of options_old contract in years.
-        /// * `underlying` - Underlying price.
-        ///
-        /// # returns:
-        /// Option vega (sensitivity to volatility)
-        fn get_vega(n: &Normal, d1: &f64, underlying: &f64, dividend: &f64, duration: &f64) -> f64 {
-            (1.0 / 100.0)
-                * underlying
-                * (-(dividend * duration)).exp()
-                * duration.sqrt()
-                * n.pdf(*d1)
-        }
-
         /// # get_theta
         /// Internal function used by get_greeks to compute option theta
         ///
         /// # args:
-        /// * `n` - A [`Normal`] struct from statrs. Used to calculate normal distributions.
+        /// * `n` - A [`StdNormal`] used to calculate normal distributions.
         /// * `d1` - d1 from [`get_d1`].
         /// * `d2` - d2 from [`get_d2`].
         /// * `opt_type` - Options type.
@@ -225,9 +761,10 @@ impl PricingModel for BlackScholesModel {
         ///
         /// # returns:
         /// Option theta (sensitivity to change in duration)
+        #[allow(clippy::too_many_arguments)]
         fn get_theta(
             opt_type: &OptTypes,
-            n: &Normal,
+            n: &StdNormal,
             d1: &f64,
             d2: &f64,
             underlying: &f64,
@@ -237,30 +774,25 @@ impl PricingModel for BlackScholesModel {
             rfr: &f64,
             volatility: &f64,
         ) -> f64 {
-            let mut ret = 0.0;
-            if *opt_type == OptTypes::Call {
-                ret = (1.0 / 365.25)
-                    * (-(((underlying * volatility * (-(dividend * duration)).exp()) / 2.0
-                        * duration.sqrt())
-                        * n.pdf(*d1))
-                        - rfr * strike * (-(rfr * duration)).exp() * n.cdf(*d2)
-                        + dividend * underlying * (-(dividend * duration)).exp() * n.cdf(*d1))
-            } else if *opt_type == OptTypes::Put {
-                ret = (1.0 / 365.25)
-                    * (-(((underlying * volatility * (-(dividend * duration)).exp()) / 2.0
-                        * duration.sqrt())
-                        * n.pdf(*d1))
-                        + rfr * strike * (-(rfr * duration)).exp() * n.cdf(-*d2)
-                        - dividend * underlying * (-(dividend * duration)).exp() * n.cdf(-*d1))
-            }
-            ret
+            let w = opt_type.sign();
+            // Decay of time value, zero without volatility or time left
+            let decay = if *volatility == 0.0 || *duration == 0.0 {
+                0.0
+            } else {
+                underlying * volatility * (-(dividend * duration)).exp() * n.pdf(*d1)
+                    / (2.0 * duration.sqrt())
+            };
+            (1.0 / DAYS_PER_YEAR)
+                * (-decay
+                    + w * (dividend * underlying * (-(dividend * duration)).exp() * n.cdf(w * d1)
+                        - rfr * strike * (-(rfr * duration)).exp() * n.cdf(w * d2)))
         }
 
         /// # get_rho
         /// Internal function used by get_greeks to compute option rho
         ///
         /// # args:
-        /// * `n` - A [`Normal`] struct from statrs. Used to calculate normal distributions.
+        /// * `n` - A [`StdNormal`] used to calculate normal distributions.
         /// * `d2` - d2 from [`get_d2`].
         /// * `opt_type` - Options type.
         /// * `duration` - Duration of options_old contract in years.
@@ -270,26 +802,31 @@ impl PricingModel for BlackScholesModel {
         /// # returns:
         /// Option rho (sensitivity to interest rate changes)
         fn get_rho(
-            n: &Normal,
+            n: &StdNormal,
             opt_type: &OptTypes,
             d2: &f64,
             strike: &f64,
             duration: &f64,
             rfr: &f64,
         ) -> f64 {
-            let mut ret = 0.0;
-            if *opt_type == OptTypes::Call {
-                ret = (1.0 / 100.0) * strike * duration * (-(rfr * duration)).exp() * n.cdf(*d2)
-            } else if *opt_type == OptTypes::Put {
-                ret = (-1.0 / 100.0) * strike * duration * (-(rfr * duration)).exp() * n.cdf(-*d2)
+            let w = opt_type.sign();
+            w * (1.0 / 100.0) * strike * duration * (-(rfr * duration)).exp() * n.cdf(w * d2)
+        }
+
+        if let Some((inner, adjusted, scales)) = self.rate_adjusted(opts) {
+            let mut gr = inner.get_greeks(&adjusted);
+            // dr'/dr is the ratio of the year fractions
+            for ((g, scale), rfr) in gr.iter_mut().zip(scales).zip(opts.opt_data.rfr.iter()) {
+                g.rho = self.rho_compounding.rho(g.rho * scale, *rfr);
             }
-            ret
+            return gr;
         }
 
         // Driver code to construct Greeks
         // Initialize Normal struct used to compute distributions
         // Structure favors efficiency and sacrifices being verbose
-        let n = Normal::new(0.0, 1.0).unwrap();
+        let n = StdNormal::new();
+        let dividend = self.dividends(opts);
 
         // Initialize return Vec
         let mut gr = Vec::with_capacity(opts.opt_data.tickers.len());
@@ -303,7 +840,7 @@ impl PricingModel for BlackScholesModel {
             d1.push(self.get_d1(
                 &opts.opt_data.underlying[i],
                 &opts.opt_data.strike[i],
-                &opts.opt_data.dividend[i],
+                &dividend[i],
                 &opts.opt_data.rfr[i],
                 &opts.opt_data.volatility[i],
                 &opts.opt_data.duration[i],
@@ -323,14 +860,14 @@ impl PricingModel for BlackScholesModel {
                     &n,
                     &opts.opt_data.opt_types[i],
                     &d1[i],
-                    &opts.opt_data.dividend[i],
+                    &dividend[i],
                     &opts.opt_data.duration[i],
                 ),
                 // get_gamma
                 gamma: get_gamma(
                     &n,
                     &d1[i],
-                    &opts.opt_data.dividend[i],
+                    &dividend[i],
                     &opts.opt_data.duration[i],
                     &opts.opt_data.underlying[i],
                     &opts.opt_data.volatility[i],
@@ -340,7 +877,7 @@ impl PricingModel for BlackScholesModel {
                     &n,
                     &d1[i],
                     &opts.opt_data.underlying[i],
-                    &opts.opt_data.dividend[i],
+                    &dividend[i],
                     &opts.opt_data.duration[i],
                 ),
                 // get_theta
@@ -350,7 +887,7 @@ impl PricingModel for BlackScholesModel {
                     &d1[i],
                     &d2[i],
                     &opts.opt_data.underlying[i],
-                    &opts.opt_data.dividend[i],
+                    &dividend[i],
                     &opts.opt_data.duration[i],
                     &opts.opt_data.strike[i],
                     &opts.opt_data.rfr[i],
@@ -367,6 +904,19 @@ impl PricingModel for BlackScholesModel {
                 ),
             })
         }
+        // At a fixed forward only discounting depends on the rate
+        if self.underlying_is_forward {
+            for ((g, price), duration) in gr
+                .iter_mut()
+                .zip(self.get_price(opts))
+                .zip(opts.opt_data.duration.iter())
+            {
+                g.rho = -(1.0 / 100.0) * duration * price;
+            }
+        }
+        for (g, rfr) in gr.iter_mut().zip(opts.opt_data.rfr.iter()) {
+            g.rho = self.rho_compounding.rho(g.rho, *rfr);
+        }
         // Return Vec<Greeks>
         gr
     }
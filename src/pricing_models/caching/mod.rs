@@ -0,0 +1,190 @@
+use super::Options;
+use super::PricingModel;
+use super::RowInputs;
+use crate::greeks::Greeks;
+use crate::options_struct::{Exercise, OptTypes};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Contract and pricing relevant inputs of a row, floats compared bitwise.
+/// Ticker, settle and maturity are included since models may read them,
+/// e.g. Monte Carlo seeds rows by contract.
+type CacheKey = (
+    String,
+    OptTypes,
+    Exercise,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    [u64; 7],
+);
+
+/// # CachingModel
+/// Wrapper memoizing prices and greeks of an inner model, keyed on each
+/// row's pricing relevant inputs. Rows seen before are not recomputed,
+/// which pays off for expensive models repricing largely unchanged chains.
+///
+/// The key holds the ticker, settle and maturity besides the numeric
+/// inputs, so models seeding or discounting by contract are cached per
+/// contract. Inner models whose results depend on anything else, e.g. row
+/// position, should not be wrapped.
+pub struct CachingModel<M: PricingModel> {
+    inner: M,
+    prices: Mutex<HashMap<CacheKey, f64>>,
+    greeks: Mutex<HashMap<CacheKey, Greeks>>,
+}
+
+impl<M: PricingModel> CachingModel<M> {
+    /// # CachingModel::new
+    /// Constructor method for CachingModel with empty caches
+    ///
+    /// # args:
+    /// * `inner` - Model computing cache misses.
+    ///
+    /// # returns:
+    /// Returns a CachingModel
+    pub fn new(inner: M) -> Self {
+        CachingModel {
+            inner,
+            prices: Mutex::new(HashMap::new()),
+            greeks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// # self.len
+    /// Number of cached prices and cached greeks.
+    pub fn len(&self) -> (usize, usize) {
+        (lock(&self.prices).len(), lock(&self.greeks).len())
+    }
+
+    /// # self.is_empty
+    /// True if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == (0, 0)
+    }
+
+    /// # self.clear
+    /// Empties both caches.
+    pub fn clear(&self) {
+        lock(&self.prices).clear();
+        lock(&self.greeks).clear();
+    }
+
+    /// # self.cached
+    /// Looks up every row in `cache`, computes the misses in one call to
+    /// `compute` and stores them. The cache is not locked while `compute`
+    /// runs, so concurrent calls only wait for each other's lookups.
+    ///
+    /// # returns:
+    /// One value per row. If `compute` returns fewer values than there are
+    /// misses, the uncomputed rows are not cached and the result stops at the
+    /// first of them, which [`Options`] reports as a length mismatch.
+    fn cached<T: Copy>(
+        &self,
+        opts: &Options,
+        cache: &Mutex<HashMap<CacheKey, T>>,
+        compute: impl Fn(&Options) -> Vec<T>,
+    ) -> Vec<T> {
+        let keys: Vec<CacheKey> = (0..opts.opt_data.tickers.len())
+            .map(|i| cache_key(opts, i))
+            .collect();
+        let misses: Vec<usize> = {
+            let cache = lock(cache);
+            // First row of every distinct key not in the cache yet
+            let mut pending: HashSet<&CacheKey> = HashSet::new();
+            (0..keys.len())
+                .filter(|&i| !cache.contains_key(&keys[i]) && pending.insert(&keys[i]))
+                .collect()
+        };
+        let computed = if misses.is_empty() {
+            Vec::new()
+        } else {
+            compute(&opts.select(&misses))
+        };
+        let fresh: HashMap<&CacheKey, T> = misses
+            .iter()
+            .zip(computed)
+            .map(|(&i, value)| (&keys[i], value))
+            .collect();
+        let mut cache = lock(cache);
+        for (key, value) in &fresh {
+            cache.insert((*key).clone(), *value);
+        }
+        keys.iter()
+            .map_while(|key| fresh.get(key).or_else(|| cache.get(key)).copied())
+            .collect()
+    }
+}
+
+/// # lock
+/// Locks a cache. A panic while holding the lock leaves the map valid, so
+/// poisoning is ignored rather than propagated.
+fn lock<T>(cache: &Mutex<T>) -> MutexGuard<'_, T> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// # cache_key
+/// Builds the cache key of row `i`.
+fn cache_key(opts: &Options, i: usize) -> CacheKey {
+    let inp = RowInputs::from_options(opts, i);
+    let d = &opts.opt_data;
+    (
+        d.tickers[i].clone(),
+        inp.opt_type,
+        inp.exercise,
+        d.settles[i],
+        d.maturities[i],
+        [
+            inp.underlying.to_bits(),
+            inp.strike.to_bits(),
+            inp.dividend.to_bits(),
+            inp.rfr.to_bits(),
+            inp.volatility.to_bits(),
+            inp.duration.to_bits(),
+            opts.opt_data.fx[i].to_bits(),
+        ],
+    )
+}
+
+// Clones carry a copy of the cached results
+impl<M: PricingModel + Clone> Clone for CachingModel<M> {
+    fn clone(&self) -> Self {
+        CachingModel {
+            inner: self.inner.clone(),
+            prices: Mutex::new(lock(&self.prices).clone()),
+            greeks: Mutex::new(lock(&self.greeks).clone()),
+        }
+    }
+}
+
+impl<M: PricingModel + Clone + Send + 'static> PricingModel for CachingModel<M> {
+    /// # self.get_price
+    /// Computes prices, reusing cached prices of identical rows
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        self.cached(opts, &self.prices, |sub| self.inner.get_price(sub))
+    }
+
+    /// # self.get_greeks
+    /// Computes greeks, reusing cached greeks of identical rows
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        self.cached(opts, &self.greeks, |sub| self.inner.get_greeks(sub))
+    }
+
+    /// # self.validate_inputs
+    /// Validates the inner model.
+    fn validate_inputs(&self, opts: &Options) -> Result<(), Vec<String>> {
+        self.inner.validate_inputs(opts)
+    }
+}
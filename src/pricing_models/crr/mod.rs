@@ -0,0 +1,189 @@
+use super::Options;
+use super::PricingModel;
+use super::{bumped_greeks, RowInputs};
+use crate::greeks::Greeks;
+use crate::options_struct::Exercise;
+
+/// # CrrModel
+/// Cox-Ross-Rubinstein binomial tree with continuous dividends.
+/// Honors the exercise style of each row, American rows may be exercised
+/// at every node. Greeks are computed by central differences.
+///
+/// With sanity checks enabled, see [`CrrModel::with_sanity_checks`], every
+/// American row is verified against its European price and intrinsic value
+/// during input validation. A violation indicates a bug in the tree.
+#[derive(Clone, Copy, Debug)]
+pub struct CrrModel {
+    steps: usize,
+    sanity_checks: bool,
+}
+
+/// Tolerance of the American price bounds checked by [`CrrModel::with_sanity_checks`].
+pub const SANITY_TOL: f64 = 1e-9;
+
+impl CrrModel {
+    /// # CrrModel::new
+    /// Constructor method for CrrModel
+    ///
+    /// # args:
+    /// * `steps` - Number of time steps in the tree, clamped to at least 1.
+    ///
+    /// # returns:
+    /// Returns a CrrModel
+    pub fn new(steps: usize) -> Self {
+        CrrModel {
+            steps: steps.max(1),
+            sanity_checks: false,
+        }
+    }
+
+    /// # self.with_sanity_checks
+    /// Enables runtime checks that American prices are at least the European
+    /// price and the intrinsic value, within [`SANITY_TOL`]. Violations are
+    /// reported by [`PricingModel::validate_inputs`], which then prices the
+    /// tree twice for American rows, intended for staging rather than production.
+    ///
+    /// # args:
+    /// * `enabled` - Whether to check prices.
+    ///
+    /// # returns:
+    /// Returns the updated CrrModel
+    pub fn with_sanity_checks(mut self, enabled: bool) -> Self {
+        self.sanity_checks = enabled;
+        self
+    }
+
+    /// # self.check_row
+    /// Checks that the American price of row `i` is at least its European
+    /// price and its intrinsic value.
+    ///
+    /// # returns:
+    /// `None`, or a message describing the violated bound.
+    fn check_row(&self, i: usize, inp: &RowInputs, price: f64) -> Option<String> {
+        if inp.exercise != Exercise::American {
+            return None;
+        }
+        let european = self.price_row(&RowInputs {
+            exercise: Exercise::European,
+            ..*inp
+        });
+        let intrinsic = inp.opt_type.payoff(inp.underlying, inp.strike);
+        if price < european - SANITY_TOL {
+            Some(format!(
+                "row {}: American price {} below European price {}",
+                i, price, european
+            ))
+        } else if price < intrinsic - SANITY_TOL {
+            Some(format!(
+                "row {}: American price {} below intrinsic value {}",
+                i, price, intrinsic
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// # self.price_row
+    /// Prices a single row by backward induction through the tree.
+    ///
+    /// # returns:
+    /// Price of the row.
+    pub(crate) fn price_row(&self, inp: &RowInputs) -> f64 {
+        let intrinsic = |spot: f64| inp.opt_type.payoff(spot, inp.strike);
+        if inp.duration <= 0.0 {
+            return intrinsic(inp.underlying);
+        }
+        let n = self.steps;
+        let dt = inp.duration / n as f64;
+        let u = (inp.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = (((inp.rfr - inp.dividend) * dt).exp() - d) / (u - d);
+        let disc = (-inp.rfr * dt).exp();
+
+        // Option values at maturity, node j has j down moves
+        let mut values: Vec<f64> = (0..=n)
+            .map(|j| intrinsic(inp.underlying * u.powi((n - j) as i32) * d.powi(j as i32)))
+            .collect();
+        for step in (0..n).rev() {
+            for j in 0..=step {
+                let cont = disc * (p * values[j] + (1.0 - p) * values[j + 1]);
+                values[j] = match inp.exercise {
+                    Exercise::European => cont,
+                    Exercise::American => {
+                        let spot = inp.underlying * u.powi((step - j) as i32) * d.powi(j as i32);
+                        cont.max(intrinsic(spot))
+                    }
+                };
+            }
+        }
+        values[0]
+    }
+}
+
+impl Default for CrrModel {
+    /// # default
+    /// CrrModel with 200 steps.
+    fn default() -> Self {
+        CrrModel::new(200)
+    }
+}
+
+impl PricingModel for CrrModel {
+    /// # self.get_price
+    /// Computes prices
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        RowInputs::all(opts)
+            .iter()
+            .map(|inp| self.price_row(inp))
+            .collect()
+    }
+
+    /// # self.get_greeks
+    /// Computes option greeks by bump and revalue
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        RowInputs::all(opts)
+            .iter()
+            .map(|inp| bumped_greeks(inp, |bumped| self.price_row(bumped)))
+            .collect()
+    }
+
+    /// # self.validate_inputs
+    /// Requires a positive volatility on every row, the tree degenerates
+    /// otherwise. With sanity checks enabled also prices the American rows
+    /// and reports those breaking the bounds of [`CrrModel::with_sanity_checks`].
+    fn validate_inputs(&self, opts: &Options) -> Result<(), Vec<String>> {
+        let mut errors: Vec<String> = opts
+            .opt_data
+            .volatility
+            .iter()
+            .enumerate()
+            .filter(|(_, vol)| vol.is_nan() || **vol <= 0.0)
+            .map(|(i, vol)| format!("row {}: CRR needs a positive volatility, got {}", i, vol))
+            .collect();
+        if self.sanity_checks && errors.is_empty() {
+            errors.extend(
+                RowInputs::all(opts)
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, inp)| self.check_row(i, inp, self.price_row(inp))),
+            );
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use super::Options;
+use super::{merge_validations, PricingModel};
+use crate::greeks::Greeks;
+use crate::options_struct::Exercise;
+use crate::pricing_models::black_scholes::BlackScholesModel;
+use crate::pricing_models::crr::CrrModel;
+
+/// # ExerciseDispatchModel
+/// Prices each row with a model chosen by its exercise style, see
+/// [`OptData::exercise`](crate::opt_data::OptData::exercise).
+/// Defaults to Black-Scholes for European and a CRR tree for American rows.
+#[derive(Clone)]
+pub struct ExerciseDispatchModel {
+    european: Box<dyn PricingModel + Send>,
+    american: Box<dyn PricingModel + Send>,
+}
+
+impl ExerciseDispatchModel {
+    /// # ExerciseDispatchModel::new
+    /// Constructor method for ExerciseDispatchModel
+    ///
+    /// # args:
+    /// * `european` - Model used for European rows.
+    /// * `american` - Model used for American rows, has to support early exercise.
+    ///
+    /// # returns:
+    /// Returns an ExerciseDispatchModel
+    pub fn new(
+        european: Box<dyn PricingModel + Send>,
+        american: Box<dyn PricingModel + Send>,
+    ) -> Self {
+        ExerciseDispatchModel { european, american }
+    }
+
+    /// # self.dispatch
+    /// Splits rows by exercise style, applies the matching model to each
+    /// part and merges the results back into row order. A model returning
+    /// fewer results than rows cuts the output at the first missing row,
+    /// which [`Options`] reports as a length mismatch.
+    fn dispatch<T: Clone, F>(&self, opts: &Options, f: F) -> Vec<T>
+    where
+        F: Fn(&dyn PricingModel, &Options) -> Vec<T>,
+    {
+        let (american, european): (Vec<usize>, Vec<usize>) = (0..opts.opt_data.tickers.len())
+            .partition(|&i| opts.opt_data.exercise[i] == Exercise::American);
+        let mut ret: Vec<Option<T>> = vec![None; opts.opt_data.tickers.len()];
+        for (model, rows) in [(&self.european, european), (&self.american, american)] {
+            if rows.is_empty() {
+                continue;
+            }
+            let results = f(model.as_ref(), &opts.select(&rows));
+            for (row, result) in rows.into_iter().zip(results) {
+                ret[row] = Some(result);
+            }
+        }
+        ret.into_iter().map_while(|r| r).collect()
+    }
+}
+
+impl Default for ExerciseDispatchModel {
+    fn default() -> Self {
+        ExerciseDispatchModel::new(
+            Box::new(BlackScholesModel::new()),
+            Box::new(CrrModel::default()),
+        )
+    }
+}
+
+impl PricingModel for ExerciseDispatchModel {
+    /// # self.get_price
+    /// Computes prices with the model matching each row's exercise style
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        self.dispatch(opts, |model, sub| model.get_price(sub))
+    }
+
+    /// # self.get_greeks
+    /// Computes greeks with the model matching each row's exercise style
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        self.dispatch(opts, |model, sub| model.get_greeks(sub))
+    }
+
+    /// # self.validate_inputs
+    /// Validates both the European and the American model.
+    fn validate_inputs(&self, opts: &Options) -> Result<(), Vec<String>> {
+        merge_validations([
+            self.european.validate_inputs(opts),
+            self.american.validate_inputs(opts),
+        ])
+    }
+}
@@ -0,0 +1,90 @@
+use super::Options;
+use super::PricingModel;
+use super::RowInputs;
+use crate::greeks::Greeks;
+use crate::opt_data::DAYS_PER_YEAR;
+
+/// # ForwardIntrinsicModel
+/// Prices every row at the discounted intrinsic value of the forward, i.e.
+/// without optionality. A lower bound of European prices for any non-negative
+/// volatility, intended as a floor and as a baseline in tests of real models.
+///
+/// Greeks are those of the intrinsic value: delta steps from 0 to the
+/// discounted dividend factor at the forward strike, gamma and vega are 0.
+/// Exercise styles are ignored, every row is priced as European.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ForwardIntrinsicModel;
+
+impl ForwardIntrinsicModel {
+    /// # ForwardIntrinsicModel::new
+    /// Constructor method for ForwardIntrinsicModel
+    ///
+    /// # returns:
+    /// Returns a ForwardIntrinsicModel
+    pub fn new() -> Self {
+        ForwardIntrinsicModel
+    }
+
+    /// # self.price_row
+    /// Discounted intrinsic value of the forward of a single row.
+    fn price_row(&self, inp: &RowInputs) -> f64 {
+        let spot = inp.underlying * (-inp.dividend * inp.duration).exp();
+        let strike = inp.strike * (-inp.rfr * inp.duration).exp();
+        inp.opt_type.payoff(spot, strike)
+    }
+
+    /// # self.greeks_row
+    /// Greeks of the discounted intrinsic value of a single row, scaled like
+    /// the Black-Scholes greeks: rho per 1% change and theta per calendar day.
+    fn greeks_row(&self, inp: &RowInputs) -> Greeks {
+        let dividend_df = (-inp.dividend * inp.duration).exp();
+        let rate_df = (-inp.rfr * inp.duration).exp();
+        let spot = inp.underlying * dividend_df;
+        let strike = inp.strike * rate_df;
+        // +1 for long the forward, -1 for short, 0 out of the money
+        let sign = if inp.opt_type.payoff(spot, strike) > 0.0 {
+            inp.opt_type.sign()
+        } else {
+            0.0
+        };
+        Greeks {
+            delta: sign * dividend_df,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: sign * (inp.dividend * spot - inp.rfr * strike) / DAYS_PER_YEAR,
+            rho: sign * inp.duration * strike / 100.0,
+        }
+    }
+}
+
+impl PricingModel for ForwardIntrinsicModel {
+    /// # self.get_price
+    /// Computes discounted forward intrinsic values
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        RowInputs::all(opts)
+            .iter()
+            .map(|inp| self.price_row(inp))
+            .collect()
+    }
+
+    /// # self.get_greeks
+    /// Computes greeks of the discounted forward intrinsic values
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        RowInputs::all(opts)
+            .iter()
+            .map(|inp| self.greeks_row(inp))
+            .collect()
+    }
+}
@@ -0,0 +1,134 @@
+use super::Options;
+use super::{merge_validations, PricingModel};
+use crate::greeks::Greeks;
+
+/// # FxMode
+/// How [`FxAdjustedModel`] applies [`OptData::fx`](crate::opt_data::OptData::fx).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FxMode {
+    /// # FxMode::Convert
+    /// Converts the underlying to the strike currency before pricing.
+    /// Prices are in strike currency, delta is per unit of converted underlying.
+    Convert,
+    /// # FxMode::Quanto
+    /// Prices in underlying currency with the drift adjusted by
+    /// `-correlation * volatility * fx_volatility`, then pays out at the
+    /// fixed FX rate. Prices and greeks are scaled by the FX rate.
+    Quanto {
+        /// Correlation between the underlying and the FX rate.
+        correlation: f64,
+        /// Annualized volatility of the FX rate.
+        fx_volatility: f64,
+    },
+}
+
+/// # FxAdjustedModel
+/// Wrapper pricing options whose underlying and strike are quoted in
+/// different currencies, delegating to an inner model.
+#[derive(Clone)]
+pub struct FxAdjustedModel {
+    inner: Box<dyn PricingModel + Send>,
+    mode: FxMode,
+}
+
+impl FxAdjustedModel {
+    /// # FxAdjustedModel::new
+    /// Constructor method for FxAdjustedModel
+    ///
+    /// # args:
+    /// * `inner` - Model pricing the adjusted inputs.
+    /// * `mode` - See [`FxMode`].
+    ///
+    /// # returns:
+    /// Returns an FxAdjustedModel
+    pub fn new(inner: Box<dyn PricingModel + Send>, mode: FxMode) -> Self {
+        FxAdjustedModel { inner, mode }
+    }
+
+    /// # self.adjust
+    /// Copies `opts` with inputs adjusted for the FX mode.
+    fn adjust(&self, opts: &Options) -> Options {
+        let rows: Vec<usize> = (0..opts.opt_data.tickers.len()).collect();
+        let mut adjusted = opts.select(&rows);
+        let d = &mut adjusted.opt_data;
+        for i in rows {
+            match self.mode {
+                FxMode::Convert => d.underlying[i] *= d.fx[i],
+                FxMode::Quanto {
+                    correlation,
+                    fx_volatility,
+                } => d.dividend[i] += correlation * d.volatility[i] * fx_volatility,
+            }
+        }
+        adjusted
+    }
+
+    /// # self.payout_scale
+    /// Factor converting inner results of row `i` to the strike currency.
+    fn payout_scale(&self, opts: &Options, i: usize) -> f64 {
+        match self.mode {
+            FxMode::Convert => 1.0,
+            FxMode::Quanto { .. } => opts.opt_data.fx[i],
+        }
+    }
+}
+
+impl PricingModel for FxAdjustedModel {
+    /// # self.get_price
+    /// Computes prices on FX adjusted inputs
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices in strike currency.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        self.inner
+            .get_price(&self.adjust(opts))
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| p * self.payout_scale(opts, i))
+            .collect()
+    }
+
+    /// # self.get_greeks
+    /// Computes greeks on FX adjusted inputs
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`] in strike currency.
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        self.inner
+            .get_greeks(&self.adjust(opts))
+            .into_iter()
+            .enumerate()
+            .map(|(i, g)| {
+                let scale = self.payout_scale(opts, i);
+                Greeks {
+                    delta: g.delta * scale,
+                    gamma: g.gamma * scale,
+                    vega: g.vega * scale,
+                    theta: g.theta * scale,
+                    rho: g.rho * scale,
+                }
+            })
+            .collect()
+    }
+
+    /// # self.validate_inputs
+    /// Requires a positive FX rate on every row, then validates the inner model.
+    fn validate_inputs(&self, opts: &Options) -> Result<(), Vec<String>> {
+        let fx: Vec<String> = opts
+            .opt_data
+            .fx
+            .iter()
+            .enumerate()
+            .filter(|(_, fx)| !(**fx > 0.0 && fx.is_finite()))
+            .map(|(i, fx)| format!("row {}: fx has to be positive, got {}", i, fx))
+            .collect();
+        let fx = if fx.is_empty() { Ok(()) } else { Err(fx) };
+        merge_validations([fx, self.inner.validate_inputs(opts)])
+    }
+}
@@ -0,0 +1,140 @@
+use super::black_scholes::BlackScholesModel;
+use super::crr::CrrModel;
+use super::dispatch::ExerciseDispatchModel;
+use super::forward_intrinsic::ForwardIntrinsicModel;
+use super::monte_carlo::MonteCarloModel;
+use super::PricingModel;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// # ModelKind
+/// Pricing models selectable by name, e.g. from a config file. The names
+/// read by `FromStr` and written by `Display` are stable.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ModelKind {
+    /// `black-scholes`, see [`BlackScholesModel`].
+    BlackScholes,
+    /// `black76`, Black-Scholes reading the underlying as the forward.
+    Black76,
+    /// `crr`, see [`CrrModel`].
+    Crr,
+    /// `monte-carlo`, see [`MonteCarloModel`].
+    MonteCarlo,
+    /// `forward-intrinsic`, see [`ForwardIntrinsicModel`].
+    ForwardIntrinsic,
+    /// `exercise-dispatch`, see [`ExerciseDispatchModel`].
+    ExerciseDispatch,
+}
+
+impl ModelKind {
+    /// Every kind, in the order listed in [`UnknownModelError`].
+    pub const ALL: [ModelKind; 6] = [
+        ModelKind::BlackScholes,
+        ModelKind::Black76,
+        ModelKind::Crr,
+        ModelKind::MonteCarlo,
+        ModelKind::ForwardIntrinsic,
+        ModelKind::ExerciseDispatch,
+    ];
+
+    /// # self.name
+    /// Stable name of the kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModelKind::BlackScholes => "black-scholes",
+            ModelKind::Black76 => "black76",
+            ModelKind::Crr => "crr",
+            ModelKind::MonteCarlo => "monte-carlo",
+            ModelKind::ForwardIntrinsic => "forward-intrinsic",
+            ModelKind::ExerciseDispatch => "exercise-dispatch",
+        }
+    }
+}
+
+impl FromStr for ModelKind {
+    /// # FromStr
+    /// Implements FromStr to construct ModelKind from its name, case insensitive.
+    type Err = UnknownModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        ModelKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| UnknownModelError(s.to_string()))
+    }
+}
+
+impl fmt::Display for ModelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// # UnknownModelError
+/// Returned when parsing a [`ModelKind`] from a name that is not listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownModelError(pub String);
+
+impl fmt::Display for UnknownModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = ModelKind::ALL.iter().map(ModelKind::name).collect();
+        write!(
+            f,
+            "unknown pricing model '{}', expected one of {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl Error for UnknownModelError {}
+
+/// # ModelParams
+/// Parameters of the models built by [`build_model`], ignored by models
+/// that do not use them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModelParams {
+    /// Tree steps of [`ModelKind::Crr`] and American rows of
+    /// [`ModelKind::ExerciseDispatch`].
+    pub steps: usize,
+    /// Paths per option of [`ModelKind::MonteCarlo`].
+    pub paths: usize,
+    /// Seed of [`ModelKind::MonteCarlo`].
+    pub seed: u64,
+}
+
+impl Default for ModelParams {
+    /// # default
+    /// 200 tree steps like [`CrrModel::default`], 10,000 paths and seed 0.
+    fn default() -> Self {
+        ModelParams {
+            steps: 200,
+            paths: 10_000,
+            seed: 0,
+        }
+    }
+}
+
+/// # build_model
+/// Constructs the model of a [`ModelKind`].
+///
+/// # args:
+/// * `kind` - Model to build.
+/// * `params` - See [`ModelParams`].
+///
+/// # returns:
+/// The boxed model, ready to pass to [`Options`](crate::options_struct::Options).
+pub fn build_model(kind: ModelKind, params: ModelParams) -> Box<dyn PricingModel + Send> {
+    match kind {
+        ModelKind::BlackScholes => Box::new(BlackScholesModel::new()),
+        ModelKind::Black76 => Box::new(BlackScholesModel::new().with_underlying_is_forward(true)),
+        ModelKind::Crr => Box::new(CrrModel::new(params.steps)),
+        ModelKind::MonteCarlo => Box::new(MonteCarloModel::new(params.paths, params.seed)),
+        ModelKind::ForwardIntrinsic => Box::new(ForwardIntrinsicModel::new()),
+        ModelKind::ExerciseDispatch => Box::new(ExerciseDispatchModel::new(
+            Box::new(BlackScholesModel::new()),
+            Box::new(CrrModel::new(params.steps)),
+        )),
+    }
+}
@@ -1,11 +1,189 @@
 pub mod black_scholes;
+pub mod caching;
+pub mod crr;
+pub mod dispatch;
+pub mod forward_intrinsic;
+pub mod fx;
+pub mod kind;
+pub mod monte_carlo;
+pub mod normal;
+pub mod shifted_vol;
 
-use crate::greeks::Greeks;
-use crate::options_struct::Options;
+use crate::greeks::{BumpedPrices, Greeks};
+use crate::opt_data::DAYS_PER_YEAR;
+use crate::options_struct::{Exercise, OptTypes, Options};
 
 /// # PricingModel
 /// Trait required to pass a model to [`Options`].
-pub trait PricingModel {
+/// Models have to be cloneable, see [`PricingModelClone`], and shareable
+/// across threads, so that `&Options` can be priced from several threads at
+/// once. Models caching state use a lock, see
+/// [`CachingModel`](caching::CachingModel).
+pub trait PricingModel: PricingModelClone + Send + Sync {
     fn get_price(&self, opts: &Options) -> Vec<f64>;
     fn get_greeks(&self, opts: &Options) -> Vec<Greeks>;
+
+    /// # self.validate_inputs
+    /// Checks that `opts` holds the inputs the model requires, before pricing.
+    /// Models without requirements beyond the standard columns keep the default.
+    ///
+    /// # args:
+    /// * `opts` - Options to be priced.
+    ///
+    /// # returns:
+    /// Ok, or one message per missing or invalid input.
+    fn validate_inputs(&self, _opts: &Options) -> Result<(), Vec<String>> {
+        Ok(())
+    }
+
+    /// # self.rate_shift_prices
+    /// Prices of `opts` at every parallel shift of the risk free rate, for
+    /// models that can reprice rate shifts without a full reprice, see
+    /// [`Options::scenario_rate`]. Models without such a path keep the default.
+    ///
+    /// # args:
+    /// * `opts` - Options to be priced.
+    /// * `rate_shifts` - Parallel rate shifts as decimals.
+    ///
+    /// # returns:
+    /// Prices of every row per shift, or `None` to reprice in full.
+    fn rate_shift_prices(&self, _opts: &Options, _rate_shifts: &[f64]) -> Option<Vec<Vec<f64>>> {
+        None
+    }
+}
+
+/// # merge_validations
+/// Combines the results of several [`PricingModel::validate_inputs`] calls,
+/// keeping every message.
+pub(crate) fn merge_validations<I>(results: I) -> Result<(), Vec<String>>
+where
+    I: IntoIterator<Item = Result<(), Vec<String>>>,
+{
+    let errors: Vec<String> = results
+        .into_iter()
+        .filter_map(Result::err)
+        .flatten()
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// # PricingModelClone
+/// Clones a boxed [`PricingModel`], required to clone [`Options`].
+/// Implemented for every model that is `Clone + Send + 'static`.
+pub trait PricingModelClone {
+    fn clone_box(&self) -> Box<dyn PricingModel + Send>;
+}
+
+impl<T: PricingModel + Clone + Send + 'static> PricingModelClone for T {
+    fn clone_box(&self) -> Box<dyn PricingModel + Send> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn PricingModel + Send> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// # RowInputs
+/// Copy of the inputs of a single row. Used by models pricing row by row,
+/// and to move rows across threads without the rest of the chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct RowInputs {
+    pub opt_type: OptTypes,
+    pub exercise: Exercise,
+    pub underlying: f64,
+    pub strike: f64,
+    pub dividend: f64,
+    pub rfr: f64,
+    pub volatility: f64,
+    pub duration: f64,
+}
+
+impl RowInputs {
+    /// # RowInputs::from_options
+    /// Copies the inputs of row `i`.
+    pub fn from_options(opts: &Options, i: usize) -> Self {
+        RowInputs {
+            opt_type: opts.opt_data.opt_types[i],
+            exercise: opts.opt_data.exercise[i],
+            underlying: opts.opt_data.underlying[i],
+            strike: opts.opt_data.strike[i],
+            dividend: opts.opt_data.dividend[i],
+            rfr: opts.opt_data.rfr[i],
+            volatility: opts.opt_data.volatility[i],
+            duration: opts.opt_data.duration[i],
+        }
+    }
+
+    /// # RowInputs::all
+    /// Copies the inputs of every row.
+    pub fn all(opts: &Options) -> Vec<Self> {
+        (0..opts.opt_data.tickers.len())
+            .map(|i| RowInputs::from_options(opts, i))
+            .collect()
+    }
+}
+
+/// # bumped_greeks
+/// Computes greeks of a single row by central differences of `price`.
+/// Scaled like the Black-Scholes greeks: vega and rho per 1% change and
+/// theta per calendar day. Used by models without analytic greeks.
+///
+/// # args:
+/// * `inp` - Inputs of the row.
+/// * `price` - Prices a row at the given inputs.
+///
+/// # returns:
+/// [`Greeks`] of the row.
+pub(crate) fn bumped_greeks<F: Fn(&RowInputs) -> f64>(inp: &RowInputs, price: F) -> Greeks {
+    let base = price(inp);
+    let ds = inp.underlying * 0.01;
+    let spot = BumpedPrices {
+        base,
+        up: price(&RowInputs {
+            underlying: inp.underlying + ds,
+            ..*inp
+        }),
+        down: price(&RowInputs {
+            underlying: inp.underlying - ds,
+            ..*inp
+        }),
+        bump: ds,
+    };
+    let bump = 0.0001;
+    let volatility = BumpedPrices {
+        base,
+        up: price(&RowInputs {
+            volatility: inp.volatility + bump,
+            ..*inp
+        }),
+        down: price(&RowInputs {
+            volatility: inp.volatility - bump,
+            ..*inp
+        }),
+        bump,
+    };
+    let rfr = BumpedPrices {
+        base,
+        up: price(&RowInputs {
+            rfr: inp.rfr + bump,
+            ..*inp
+        }),
+        down: price(&RowInputs {
+            rfr: inp.rfr - bump,
+            ..*inp
+        }),
+        bump,
+    };
+    let aged = price(&RowInputs {
+        duration: (inp.duration - 1.0 / DAYS_PER_YEAR).max(0.0),
+        ..*inp
+    });
+    Greeks::finite_difference_from_prices(&spot, &volatility, &rfr, aged)
 }
@@ -0,0 +1,233 @@
+use super::normal::std_normal;
+use super::Options;
+use super::PricingModel;
+use super::{bumped_greeks, RowInputs};
+use crate::greeks::Greeks;
+use crate::opt_data::OptData;
+use rand::distributions::Distribution;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::marker::PhantomData;
+use std::thread;
+
+/// # MonteCarloModel
+/// Model to compute prices and greeks by simulating terminal prices under
+/// geometric Brownian motion with continuous dividends.
+///
+/// Every row draws from its own generator seeded deterministically from the
+/// base seed and a key of the contract, see [`row_key`]. Results therefore
+/// only depend on the seed and the inputs, never on row order, chunking, the
+/// number of threads or how rows are scheduled.
+/// The generator is generic over any [`SeedableRng`] so tests can inject a
+/// specific one, it defaults to [`ChaCha8Rng`].
+///
+/// Greeks are computed by central differences using common random numbers.
+/// Exercise styles are ignored, every row is priced as European.
+pub struct MonteCarloModel<R = ChaCha8Rng> {
+    paths: usize,
+    seed: u64,
+    threads: usize,
+    rng: PhantomData<fn() -> R>,
+}
+
+// Manual impl, the generator type itself does not have to be Clone
+impl<R> Clone for MonteCarloModel<R> {
+    fn clone(&self) -> Self {
+        MonteCarloModel {
+            paths: self.paths,
+            seed: self.seed,
+            threads: self.threads,
+            rng: PhantomData,
+        }
+    }
+}
+
+impl MonteCarloModel<ChaCha8Rng> {
+    /// # MonteCarloModel::new
+    /// Constructor method for MonteCarloModel using the default generator.
+    ///
+    /// # args:
+    /// * `paths` - Number of simulated paths per option.
+    /// * `seed` - Base seed, identical seeds give identical prices.
+    ///
+    /// # returns:
+    /// Returns a MonteCarloModel
+    pub fn new(paths: usize, seed: u64) -> Self {
+        MonteCarloModel::with_rng(paths, seed)
+    }
+}
+
+impl<R: SeedableRng + RngCore> MonteCarloModel<R> {
+    /// # MonteCarloModel::with_rng
+    /// Constructor method for MonteCarloModel with an explicit generator type.
+    ///
+    /// # args:
+    /// * `paths` - Number of simulated paths per option.
+    /// * `seed` - Base seed, identical seeds give identical prices.
+    ///
+    /// # returns:
+    /// Returns a MonteCarloModel
+    pub fn with_rng(paths: usize, seed: u64) -> Self {
+        MonteCarloModel {
+            paths,
+            seed,
+            threads: 1,
+            rng: PhantomData,
+        }
+    }
+
+    /// # self.with_threads
+    /// Sets the number of threads used to price rows. Does not affect results.
+    ///
+    /// # args:
+    /// * `threads` - Number of threads, clamped to at least 1.
+    ///
+    /// # returns:
+    /// Returns the updated MonteCarloModel
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// # self.row_rng
+    /// Deterministically derives the generator of a single row from the base seed.
+    ///
+    /// # args:
+    /// * `key` - Key of the row, see [`row_key`].
+    ///
+    /// # returns:
+    /// A freshly seeded generator for the row.
+    pub fn row_rng(&self, key: u64) -> R {
+        R::seed_from_u64(split_mix(self.seed ^ split_mix(key)))
+    }
+
+    /// # self.simulate
+    /// Prices a single row by simulation.
+    ///
+    /// # returns:
+    /// Discounted mean payoff.
+    fn simulate(&self, inp: &RowInputs, key: u64) -> f64 {
+        let n = std_normal();
+        let mut rng = self.row_rng(key);
+        let drift = (inp.rfr - inp.dividend - inp.volatility.powf(2.0) / 2.0) * inp.duration;
+        let diffusion = inp.volatility * inp.duration.sqrt();
+        let mut payoff_sum = 0.0;
+        for _ in 0..self.paths {
+            let terminal = inp.underlying * (drift + diffusion * n.sample(&mut rng)).exp();
+            payoff_sum += inp.opt_type.payoff(terminal, inp.strike);
+        }
+        (-inp.rfr * inp.duration).exp() * payoff_sum / self.paths as f64
+    }
+
+    /// # self.run
+    /// Applies `f` to every row and its [`row_key`], splitting rows into
+    /// contiguous blocks across threads.
+    ///
+    /// # returns:
+    /// Results in row order.
+    fn run<T, F>(&self, opts: &Options, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&RowInputs, u64) -> T + Sync,
+    {
+        let rows: Vec<(RowInputs, u64)> = RowInputs::all(opts)
+            .into_iter()
+            .enumerate()
+            .map(|(i, inp)| (inp, row_key(&opts.opt_data, i)))
+            .collect();
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let block = rows.len().div_ceil(self.threads);
+        let f = &f;
+        thread::scope(|s| {
+            let handles: Vec<_> = rows
+                .chunks(block)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(inp, key)| f(inp, *key))
+                            .collect::<Vec<T>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("Monte Carlo worker panicked"))
+                .collect()
+        })
+    }
+}
+
+impl<R: SeedableRng + RngCore + 'static> PricingModel for MonteCarloModel<R> {
+    /// # self.get_price
+    /// Computes prices by simulation
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        self.run(opts, |inp, key| self.simulate(inp, key))
+    }
+
+    /// # self.get_greeks
+    /// Computes option greeks by bump and revalue with common random numbers
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        self.run(opts, |inp, key| {
+            bumped_greeks(inp, |bumped| self.simulate(bumped, key))
+        })
+    }
+
+    /// # self.validate_inputs
+    /// Requires at least one simulated path.
+    fn validate_inputs(&self, _opts: &Options) -> Result<(), Vec<String>> {
+        if self.paths == 0 {
+            return Err(vec!["Monte Carlo needs at least one path".to_string()]);
+        }
+        Ok(())
+    }
+}
+
+/// # row_key
+/// Stable key of a contract from its ticker, option type, strike and maturity.
+/// Uses FNV-1a so keys do not change across platforms or Rust releases.
+/// Identical contracts share a key and thus their random numbers.
+///
+/// # args:
+/// * `data` - Option data holding the row.
+/// * `i` - Row index.
+///
+/// # returns:
+/// The key of the row.
+pub fn row_key(data: &OptData, i: usize) -> u64 {
+    const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+    let maturity = data.maturities[i];
+    data.tickers[i]
+        .bytes()
+        .chain([0xFF, data.opt_types[i] as u8])
+        .chain(data.strike[i].to_bits().to_le_bytes())
+        .chain(maturity.timestamp().to_le_bytes())
+        .chain(maturity.timestamp_subsec_nanos().to_le_bytes())
+        .fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
+
+/// # split_mix
+/// SplitMix64 finalizer, used to spread seeds derived from contract keys.
+fn split_mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,116 @@
+use statrs::distribution::Normal;
+#[cfg(not(feature = "fast-math"))]
+use statrs::distribution::{Continuous, ContinuousCDF};
+use std::sync::OnceLock;
+
+const INV_SQRT_2PI: f64 = 0.398_942_280_401_432_7;
+
+/// Largest absolute error of [`fast_cdf`] against the exact normal cdf.
+pub const FAST_CDF_MAX_ERROR: f64 = 1e-7;
+
+/// # std_normal
+/// Shared standard normal distribution, constructed once on first use.
+/// Models needing a [`Normal`], e.g. to sample or for probabilities, should
+/// use this instead of constructing their own.
+///
+/// # returns:
+/// A reference to the standard normal distribution.
+pub fn std_normal() -> &'static Normal {
+    static STD_NORMAL: OnceLock<Normal> = OnceLock::new();
+    STD_NORMAL.get_or_init(|| Normal::new(0.0, 1.0).unwrap())
+}
+
+/// # StdNormal
+/// Standard normal distribution used by the closed form models.
+///
+/// Backed by statrs by default. With the `fast-math` feature the cdf uses the
+/// polynomial approximation of [`fast_cdf`], absolute error below
+/// [`FAST_CDF_MAX_ERROR`], and
+/// the pdf is evaluated directly.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdNormal {
+    #[cfg(not(feature = "fast-math"))]
+    inner: StatrsNormal,
+}
+
+#[cfg(not(feature = "fast-math"))]
+#[derive(Clone, Copy, Debug)]
+struct StatrsNormal(Normal);
+
+#[cfg(not(feature = "fast-math"))]
+impl Default for StatrsNormal {
+    fn default() -> Self {
+        StatrsNormal(*std_normal())
+    }
+}
+
+impl StdNormal {
+    /// # StdNormal::new
+    /// Constructor method for StdNormal
+    pub fn new() -> Self {
+        StdNormal::default()
+    }
+
+    /// # self.cdf
+    /// Cumulative distribution function.
+    #[cfg(not(feature = "fast-math"))]
+    pub fn cdf(&self, x: f64) -> f64 {
+        self.inner.0.cdf(x)
+    }
+
+    /// # self.cdf
+    /// Cumulative distribution function, see [`fast_cdf`].
+    #[cfg(feature = "fast-math")]
+    pub fn cdf(&self, x: f64) -> f64 {
+        fast_cdf(x)
+    }
+
+    /// # self.pdf
+    /// Probability density function.
+    #[cfg(not(feature = "fast-math"))]
+    pub fn pdf(&self, x: f64) -> f64 {
+        self.inner.0.pdf(x)
+    }
+
+    /// # self.pdf
+    /// Probability density function.
+    #[cfg(feature = "fast-math")]
+    pub fn pdf(&self, x: f64) -> f64 {
+        fast_pdf(x)
+    }
+}
+
+/// # fast_pdf
+/// Standard normal density evaluated directly.
+pub fn fast_pdf(x: f64) -> f64 {
+    INV_SQRT_2PI * (-0.5 * x * x).exp()
+}
+
+/// # fast_cdf
+/// Standard normal cdf by the Abramowitz-Stegun 26.2.17 polynomial
+/// approximation, absolute error below 7.5e-8.
+///
+/// # args:
+/// * `x` - Point to evaluate, infinities map to 0.0 and 1.0.
+///
+/// # returns:
+/// Approximation of the cdf at `x`, NaN for NaN.
+pub fn fast_cdf(x: f64) -> f64 {
+    const P: f64 = 0.231_641_9;
+    const B: [f64; 5] = [
+        0.319_381_530,
+        -0.356_563_782,
+        1.781_477_937,
+        -1.821_255_978,
+        1.330_274_429,
+    ];
+    let z = x.abs();
+    let t = 1.0 / (1.0 + P * z);
+    let poly = t * (B[0] + t * (B[1] + t * (B[2] + t * (B[3] + t * B[4]))));
+    let upper = fast_pdf(z) * poly;
+    if x >= 0.0 {
+        1.0 - upper
+    } else {
+        upper
+    }
+}
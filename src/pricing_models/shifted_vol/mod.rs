@@ -0,0 +1,99 @@
+use super::Options;
+use super::PricingModel;
+use crate::greeks::Greeks;
+
+/// # ShiftedVolModel
+/// Wrapper pricing with stressed volatilities, delegating to an inner model.
+/// The volatility of every row becomes
+/// `volatility + shift + slope * ln(strike / underlying)`, a parallel shift
+/// plus a linear twist in log moneyness. A negative slope raises the vols of
+/// low strikes and steepens a put skew. Stressed vols are clamped at zero,
+/// like in [`Options::vega_for_shift`], so a large down shift prices rows at
+/// their deterministic value instead of at a negative vol.
+///
+/// Greeks are the inner model's at the stressed volatility, the vol change
+/// from moving spot along the skew is not included in delta.
+#[derive(Clone)]
+pub struct ShiftedVolModel<M: PricingModel> {
+    inner: M,
+    shift: f64,
+    slope: f64,
+}
+
+impl<M: PricingModel> ShiftedVolModel<M> {
+    /// # ShiftedVolModel::new
+    /// Constructor method for ShiftedVolModel
+    ///
+    /// # args:
+    /// * `inner` - Model pricing the stressed inputs.
+    /// * `shift` - Parallel vol shift, e.g. 0.02 for two vol points up.
+    /// * `slope` - Vol change per unit of `ln(strike / underlying)`.
+    ///
+    /// # returns:
+    /// Returns a ShiftedVolModel
+    pub fn new(inner: M, shift: f64, slope: f64) -> Self {
+        ShiftedVolModel {
+            inner,
+            shift,
+            slope,
+        }
+    }
+
+    /// # self.shifted_vol
+    /// Stressed volatility of a single row.
+    ///
+    /// # args:
+    /// * `volatility` - Input volatility.
+    /// * `underlying` - Underlying price.
+    /// * `strike` - Strike price.
+    ///
+    /// # returns:
+    /// The volatility passed to the inner model, at least zero.
+    pub fn shifted_vol(&self, volatility: f64, underlying: f64, strike: f64) -> f64 {
+        (volatility + self.shift + self.slope * (strike / underlying).ln()).max(0.0)
+    }
+
+    /// # self.adjust
+    /// Copies `opts` with stressed volatilities.
+    fn adjust(&self, opts: &Options) -> Options {
+        let rows: Vec<usize> = (0..opts.opt_data.tickers.len()).collect();
+        let mut adjusted = opts.select(&rows);
+        let d = &mut adjusted.opt_data;
+        for i in rows {
+            d.volatility[i] = self.shifted_vol(d.volatility[i], d.underlying[i], d.strike[i]);
+        }
+        adjusted
+    }
+}
+
+impl<M: PricingModel + Clone + Send + 'static> PricingModel for ShiftedVolModel<M> {
+    /// # self.get_price
+    /// Computes prices at stressed volatilities
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of prices.
+    fn get_price(&self, opts: &Options) -> Vec<f64> {
+        self.inner.get_price(&self.adjust(opts))
+    }
+
+    /// # self.get_greeks
+    /// Computes greeks at stressed volatilities
+    ///
+    /// # args:
+    /// * `opts` - Takes a reference to options to use for calculations.
+    ///
+    /// # returns:
+    /// A vector of [`Greeks`].
+    fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+        self.inner.get_greeks(&self.adjust(opts))
+    }
+
+    /// # self.validate_inputs
+    /// Validates the inner model on the stressed inputs.
+    fn validate_inputs(&self, opts: &Options) -> Result<(), Vec<String>> {
+        self.inner.validate_inputs(&self.adjust(opts))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::greeks::Greeks;
+use crate::options_struct::{LengthError, Options};
+
+/// # Leg
+/// A single leg of a [`Structure`]. References a row of the [`Options`]
+/// it is evaluated against together with a signed quantity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Leg {
+    /// Row index into the [`Options`] the structure is evaluated against.
+    pub idx: usize,
+    /// Signed quantity, positive for long and negative for short.
+    pub quantity: f64,
+}
+
+/// # Structure
+/// A composite position made up of several option legs, e.g. a straddle
+/// or a vertical spread. Net prices and greeks are computed from the
+/// per-leg results of an already priced [`Options`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Structure {
+    pub name: String,
+    pub legs: Vec<Leg>,
+}
+
+impl Structure {
+    /// # Structure::new
+    /// Literal constructor method for Structure
+    ///
+    /// # args:
+    /// * `name` - Name of the structure, used for reporting.
+    /// * `legs` - Vector of [`Leg`] making up the structure.
+    ///
+    /// # returns:
+    /// Returns a `Structure`.
+    pub fn new(name: &str, legs: Vec<Leg>) -> Self {
+        Structure {
+            name: name.to_string(),
+            legs,
+        }
+    }
+
+    /// # Structure::straddle
+    /// Long straddle, one long call and one long put.
+    ///
+    /// # args:
+    /// * `call_idx` - Row index of the call.
+    /// * `put_idx` - Row index of the put.
+    ///
+    /// # returns:
+    /// Returns a `Structure`.
+    pub fn straddle(call_idx: usize, put_idx: usize) -> Self {
+        Structure::new(
+            "straddle",
+            vec![
+                Leg {
+                    idx: call_idx,
+                    quantity: 1.0,
+                },
+                Leg {
+                    idx: put_idx,
+                    quantity: 1.0,
+                },
+            ],
+        )
+    }
+
+    /// # Structure::strangle
+    /// Long strangle, one long call and one long put at different strikes.
+    ///
+    /// # args:
+    /// * `call_idx` - Row index of the call.
+    /// * `put_idx` - Row index of the put.
+    ///
+    /// # returns:
+    /// Returns a `Structure`.
+    pub fn strangle(call_idx: usize, put_idx: usize) -> Self {
+        let mut structure = Structure::straddle(call_idx, put_idx);
+        structure.name = "strangle".to_string();
+        structure
+    }
+
+    /// # Structure::vertical
+    /// Vertical spread, long one option and short another of the same type.
+    ///
+    /// # args:
+    /// * `long_idx` - Row index of the long leg.
+    /// * `short_idx` - Row index of the short leg.
+    ///
+    /// # returns:
+    /// Returns a `Structure`.
+    pub fn vertical(long_idx: usize, short_idx: usize) -> Self {
+        Structure::new(
+            "vertical",
+            vec![
+                Leg {
+                    idx: long_idx,
+                    quantity: 1.0,
+                },
+                Leg {
+                    idx: short_idx,
+                    quantity: -1.0,
+                },
+            ],
+        )
+    }
+
+    /// # self.net_price
+    /// Computes the net price of the structure from the prices in `opts`.
+    ///
+    /// # args:
+    /// * `opts` - Priced [`Options`] the legs refer to.
+    ///
+    /// # returns:
+    /// Quantity weighted sum of leg prices, or a [`LengthError`] if prices
+    /// are not computed.
+    pub fn net_price(&self, opts: &Options) -> Result<f64, LengthError> {
+        let prices = opts.computed_prices()?;
+        Ok(self
+            .legs
+            .iter()
+            .map(|leg| leg.quantity * prices[leg.idx])
+            .sum())
+    }
+
+    /// # self.net_greeks
+    /// Computes the net greeks of the structure from the greeks in `opts`.
+    ///
+    /// # args:
+    /// * `opts` - [`Options`] with greeks computed that the legs refer to.
+    ///
+    /// # returns:
+    /// Quantity weighted sum of leg [`Greeks`], or a [`LengthError`] if
+    /// greeks are not computed.
+    pub fn net_greeks(&self, opts: &Options) -> Result<Greeks, LengthError> {
+        let greeks = opts.computed_greeks()?;
+        let mut net = Greeks::default();
+        for leg in &self.legs {
+            let g = &greeks[leg.idx];
+            net.delta += leg.quantity * g.delta;
+            net.gamma += leg.quantity * g.gamma;
+            net.vega += leg.quantity * g.vega;
+            net.theta += leg.quantity * g.theta;
+            net.rho += leg.quantity * g.rho;
+        }
+        Ok(net)
+    }
+}
@@ -1,25 +1,156 @@
+#[cfg(test)]
+mod fixtures {
+    use crate::opt_data::OptData;
+    use crate::options_struct::{OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use chrono::{NaiveDate, Utc};
+
+    /// Seven rows of one expiry, calls and puts alternating, strikes 105 to 135.
+    pub fn chain() -> Options {
+        let settle = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 9, 14)
+                .unwrap()
+                .and_hms_opt(2, 22, 0)
+                .unwrap(),
+            Utc,
+        );
+        let maturity = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 11, 18)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap(),
+            Utc,
+        );
+        let n = 7;
+        Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(); n],
+                (0..n)
+                    .map(|i| {
+                        if i % 2 == 0 {
+                            OptTypes::Call
+                        } else {
+                            OptTypes::Put
+                        }
+                    })
+                    .collect(),
+                vec![120.0; n],
+                (0..n).map(|i| 105.0 + 5.0 * i as f64).collect(),
+                vec![settle; n],
+                vec![maturity; n],
+                vec![0.01; n],
+                vec![0.03; n],
+                vec![0.35; n],
+            ),
+            Box::new(BlackScholesModel::new()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod test_greeks {
-    use crate::greeks::Greeks;
+    use crate::greeks::{BumpedPrices, GreekKind, Greeks};
     #[test]
     fn default() {
         let greek = Greeks::default();
         assert_eq!(greek.delta, 0.0)
     }
 
+    #[test]
+    fn finite_difference_from_prices() {
+        // Price x^2 around x = 3 with h = 0.5
+        let square = BumpedPrices {
+            base: 9.0,
+            up: 12.25,
+            down: 6.25,
+            bump: 0.5,
+        };
+        assert_eq!(square.central(), 6.0);
+        assert_eq!(square.forward(), 6.5);
+        assert_eq!(square.second(), 2.0);
+        let linear = BumpedPrices {
+            base: 0.0,
+            up: 0.01,
+            down: -0.01,
+            bump: 0.0001,
+        };
+        let greeks = Greeks::finite_difference_from_prices(&square, &linear, &linear, 8.5);
+        assert_eq!((greeks.delta, greeks.gamma, greeks.theta), (6.0, 2.0, -0.5));
+        assert!((greeks.vega - 1.0).abs() < 1e-12);
+        assert!((greeks.rho - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn as_map() {
+        let greeks = Greeks {
+            delta: 0.5,
+            gamma: 0.02,
+            vega: 0.3,
+            theta: -0.05,
+            rho: 0.1,
+        };
+        let map = greeks.as_map();
+        assert_eq!(map.len(), 5);
+        assert_eq!(map["delta"], 0.5);
+        assert_eq!(map["theta"], -0.05);
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            vec!["delta", "gamma", "rho", "theta", "vega"]
+        );
+    }
+
     #[test]
     fn display() {
         let greek = Greeks::default();
         println!("{}", greek);
     }
+
+    #[test]
+    fn two_option_hedge() {
+        let position = Greeks {
+            delta: 50.0,
+            gamma: 4.0,
+            ..Greeks::default()
+        };
+        let a = Greeks {
+            delta: 0.5,
+            gamma: 0.05,
+            ..Greeks::default()
+        };
+        let b = Greeks {
+            delta: 0.3,
+            gamma: 0.01,
+            ..Greeks::default()
+        };
+        let targets = (GreekKind::Delta, GreekKind::Gamma);
+        let (qa, qb) = crate::greeks::two_option_hedge(&position, &a, &b, targets).unwrap();
+        assert!((position.delta + qa * a.delta + qb * b.delta).abs() < 1e-9);
+        assert!((position.gamma + qa * a.gamma + qb * b.gamma).abs() < 1e-9);
+        // Proportional hedges cannot neutralize two greeks
+        assert_eq!(
+            crate::greeks::two_option_hedge(&position, &a, &a, targets),
+            None
+        );
+    }
 }
 
 #[cfg(test)]
 mod test_options {
-    use crate::opt_data::OptData;
-    use crate::options_struct::{OptTypes, Options};
+    use super::fixtures::chain;
+    use crate::calendar::DayCount;
+    use crate::greeks::{Compounding, Greeks};
+    use crate::opt_data::{OptData, DAYS_PER_YEAR};
+    use crate::options_struct::{
+        LengthError, OptRow, OptTypes, Options, Overrides, PricingError, WarningCategory,
+    };
     use crate::pricing_models::black_scholes;
-    use chrono::{NaiveDate, Utc};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::caching::CachingModel;
+    use crate::pricing_models::crr::CrrModel;
+    use crate::pricing_models::forward_intrinsic::ForwardIntrinsicModel;
+    use crate::pricing_models::monte_carlo::MonteCarloModel;
+    use crate::pricing_models::PricingModel;
+    use chrono::{NaiveDate, TimeZone, Utc};
 
     #[test]
     fn new() {
@@ -29,12 +160,18 @@ mod test_options {
                 vec![OptTypes::Call],
                 vec![120.0],
                 vec![110.0],
-                vec![chrono::DateTime::from_utc(
-                    NaiveDate::from_ymd(2022, 9, 14).and_hms(2, 22, 0),
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 9, 14)
+                        .unwrap()
+                        .and_hms_opt(2, 22, 0)
+                        .unwrap(),
                     Utc,
                 )],
-                vec![chrono::DateTime::from_utc(
-                    NaiveDate::from_ymd(2022, 11, 18).and_hms(15, 0, 0),
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 11, 18)
+                        .unwrap()
+                        .and_hms_opt(15, 0, 0)
+                        .unwrap(),
                     Utc,
                 )],
                 vec![0.03],
@@ -46,6 +183,493 @@ mod test_options {
         assert_eq!(opt.opt_data.tickers[0], *"AAPL")
     }
 
+    #[test]
+    fn price_at() {
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string()],
+                vec![OptTypes::Call],
+                vec![120.0],
+                vec![110.0],
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 9, 14)
+                        .unwrap()
+                        .and_hms_opt(2, 22, 0)
+                        .unwrap(),
+                    Utc,
+                )],
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 11, 18)
+                        .unwrap()
+                        .and_hms_opt(15, 0, 0)
+                        .unwrap(),
+                    Utc,
+                )],
+                vec![0.03],
+                vec![0.03],
+                vec![0.35],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        assert_eq!(
+            opt.price_at(0, Overrides::default()).unwrap(),
+            opt.prices[0]
+        );
+        let bumped = opt
+            .price_at(
+                0,
+                Overrides {
+                    underlying: Some(130.0),
+                    ..Overrides::default()
+                },
+            )
+            .unwrap();
+        assert!(bumped > opt.prices[0]);
+        assert_eq!(opt.opt_data.underlying[0], 120.0);
+    }
+
+    #[test]
+    fn solve_implied_vols() {
+        let settle = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 9, 14)
+                .unwrap()
+                .and_hms_opt(2, 22, 0)
+                .unwrap(),
+            Utc,
+        );
+        let maturity = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 11, 18)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap(),
+            Utc,
+        );
+        let opt_data = || {
+            OptData::new(
+                vec!["AAPL".to_string(), "AAPL".to_string()],
+                vec![OptTypes::Call, OptTypes::Put],
+                vec![120.0, 120.0],
+                vec![110.0, 125.0],
+                vec![settle, settle],
+                vec![maturity, maturity],
+                vec![0.01, 0.01],
+                vec![0.03, 0.03],
+                vec![0.35, 0.35],
+            )
+        };
+        let mut quoted = Options::new(
+            opt_data(),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        quoted.get_prices();
+        let mut opt = Options::new(
+            opt_data().with_market_prices(vec![Some(quoted.prices[0]), None]),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.opt_data.volatility = vec![0.2, 0.2];
+        opt.solve_implied_vols();
+        assert!((opt.implied_vol[0] - 0.35).abs() < 1e-6);
+        assert!(opt.implied_vol[1].is_nan());
+        assert_eq!(opt.opt_data.volatility, vec![0.2, 0.2]);
+
+        opt.get_prices();
+        let residuals = opt.residuals(&quoted.prices).unwrap();
+        assert!((residuals[1].abs_error - (opt.prices[1] - quoted.prices[1])).abs() < 1e-12);
+        assert!((residuals[1].vol_gap - 0.15).abs() < 1e-6);
+        let err = opt.residuals(&quoted.prices[..1]).unwrap_err();
+        assert_eq!((err.name, err.expected, err.found), ("market", 2, 1));
+    }
+
+    #[test]
+    fn opt_types_order() {
+        let mut types = vec![OptTypes::Put, OptTypes::Call, OptTypes::Put];
+        types.sort();
+        assert_eq!(types, vec![OptTypes::Call, OptTypes::Put, OptTypes::Put]);
+    }
+
+    #[test]
+    fn group_by_expiry() {
+        let settle = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 9, 14)
+                .unwrap()
+                .and_hms_opt(2, 22, 0)
+                .unwrap(),
+            Utc,
+        );
+        let nov = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 11, 18)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap(),
+            Utc,
+        );
+        let dec = nov + chrono::TimeDelta::days(28);
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(); 4],
+                vec![OptTypes::Call; 4],
+                vec![120.0; 4],
+                vec![110.0, 115.0, 120.0, 125.0],
+                vec![settle; 4],
+                vec![nov, dec, nov, dec],
+                vec![0.0; 4],
+                vec![0.03; 4],
+                vec![0.35; 4],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        let groups = opt.group_by_expiry();
+        assert_eq!(groups.len(), 2);
+        let nov_group = &groups[&("AAPL".to_string(), nov)];
+        assert_eq!(nov_group.opt_data.strike, vec![110.0, 120.0]);
+        assert_eq!(nov_group.prices, vec![opt.prices[0], opt.prices[2]]);
+        assert!(nov_group.greeks.is_empty());
+    }
+
+    #[test]
+    fn expected_move() {
+        let settle = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            Utc,
+        );
+        let maturity = settle + chrono::TimeDelta::days(365);
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(); 4],
+                vec![OptTypes::Call, OptTypes::Put, OptTypes::Call, OptTypes::Put],
+                vec![100.0; 4],
+                vec![100.0, 100.0, 120.0, 120.0],
+                vec![settle; 4],
+                vec![maturity; 4],
+                vec![0.0; 4],
+                vec![0.0; 4],
+                vec![0.2; 4],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        let moves = opt.expected_move().unwrap();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].1, maturity);
+        // Close to one standard deviation, spot * vol * sqrt(T)
+        assert!((moves[0].2 - 20.0).abs() < 0.5, "{}", moves[0].2);
+    }
+
+    #[test]
+    fn zero_strike() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = settle + chrono::TimeDelta::days(365);
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(); 3],
+                vec![OptTypes::Call, OptTypes::Put, OptTypes::Call],
+                vec![100.0; 3],
+                vec![0.0; 3],
+                vec![settle; 3],
+                vec![maturity, maturity, settle],
+                vec![0.02; 3],
+                vec![0.03; 3],
+                vec![0.2, 0.2, 0.0],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        opt.get_greeks();
+        assert!(opt.failed_rows().is_empty());
+        let t = opt.opt_data.duration[0];
+        let prepaid_forward = 100.0 * (-0.02 * t).exp();
+        assert!((opt.prices[0] - prepaid_forward).abs() < 1e-12);
+        assert_eq!(opt.prices[1], 0.0);
+        assert_eq!(opt.prices[2], 100.0);
+        assert!((opt.greeks[0].delta - (-0.02 * t).exp()).abs() < 1e-12);
+        assert_eq!((opt.greeks[0].gamma, opt.greeks[0].vega), (0.0, 0.0));
+        assert!((opt.greeks[0].theta - 0.02 * prepaid_forward / DAYS_PER_YEAR).abs() < 1e-12);
+        assert!(opt.greeks.iter().all(|g| g.rho == 0.0));
+    }
+
+    #[test]
+    fn representative_iv() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = settle + chrono::TimeDelta::days(365);
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(), "AAPL".to_string(), "MSFT".to_string()],
+                vec![OptTypes::Call; 3],
+                vec![100.0; 3],
+                vec![100.0, 200.0, 100.0],
+                vec![settle; 3],
+                vec![maturity; 3],
+                vec![0.0; 3],
+                vec![0.0; 3],
+                vec![0.2, 0.6, 0.3],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_greeks();
+        let ivs = opt.representative_iv().unwrap();
+        assert_eq!(ivs.len(), 2);
+        let (v0, v1) = (opt.greeks[0].vega, opt.greeks[1].vega);
+        let expected = (0.2 * v0 + 0.6 * v1) / (v0 + v1);
+        assert_eq!(ivs[0].0, "AAPL");
+        assert!((ivs[0].1 - expected).abs() < 1e-12);
+        assert!(ivs[0].1 > 0.2 && ivs[0].1 < 0.4);
+        assert_eq!(ivs[1], ("MSFT".to_string(), 0.3));
+    }
+
+    #[test]
+    fn terminal_density() {
+        let (spot, t, r, q, vol) = (100.0, 0.5, 0.03, 0.01, 0.25);
+        let h = 0.05;
+        let grid: Vec<f64> = (1..8000).map(|i| i as f64 * h).collect();
+        let density = black_scholes::terminal_density(spot, &grid, t, r, q, vol);
+        let integrate = |f: &dyn Fn(f64) -> f64| -> f64 {
+            grid.iter().zip(&density).map(|(x, d)| f(*x) * d * h).sum()
+        };
+        assert!((integrate(&|_| 1.0) - 1.0).abs() < 1e-6);
+        let forward = spot * ((r - q) * t).exp();
+        assert!((integrate(&|x| x) - forward).abs() < 1e-4);
+
+        // Discounted expected payoff reproduces the closed form price
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let opt = Options::new(
+            OptData::new(
+                vec!["PDF".to_string()],
+                vec![OptTypes::Call],
+                vec![spot],
+                vec![105.0],
+                vec![settle],
+                vec![settle],
+                vec![q],
+                vec![r],
+                vec![vol],
+            )
+            .with_durations(vec![t])
+            .unwrap(),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        let price = black_scholes::BlackScholesModel::new().get_price(&opt)[0];
+        let expected = (-r * t).exp() * integrate(&|x| (x - 105.0).max(0.0));
+        assert!((price - expected).abs() < 1e-4);
+        assert_eq!(
+            black_scholes::terminal_density(spot, &[-1.0, 0.0, 100.0], 0.0, r, q, vol),
+            vec![0.0; 3]
+        );
+    }
+
+    #[test]
+    fn vol_for_delta() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["VOL".to_string(); 3],
+                vec![OptTypes::Call, OptTypes::Put, OptTypes::Call],
+                vec![100.0; 3],
+                vec![120.0, 80.0, 90.0],
+                vec![settle; 3],
+                vec![maturity; 3],
+                vec![0.01; 3],
+                vec![0.03; 3],
+                vec![0.25, 0.4, 0.1],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_greeks();
+        let t = opt.opt_data.duration[0];
+        for i in 0..3 {
+            let vol = black_scholes::vol_for_delta(
+                opt.greeks[i].delta,
+                opt.opt_data.opt_types[i],
+                100.0,
+                opt.opt_data.strike[i],
+                t,
+                0.03,
+                0.01,
+            )
+            .unwrap();
+            assert!((vol - opt.opt_data.volatility[i]).abs() < 1e-6, "row {}", i);
+        }
+        let solve = |delta, opt_type, strike| {
+            black_scholes::vol_for_delta(delta, opt_type, 100.0, strike, t, 0.03, 0.01)
+        };
+        assert_eq!(solve(1.0, OptTypes::Call, 120.0), None);
+        assert_eq!(solve(0.3, OptTypes::Put, 80.0), None);
+        // In the money calls never have a delta below N(2 sqrt(ab)), about 0.69 here
+        assert_eq!(solve(0.6, OptTypes::Call, 90.0), None);
+    }
+
+    #[test]
+    fn opt_type_helpers() {
+        assert!(OptTypes::Call.is_call() && !OptTypes::Call.is_put());
+        assert!(OptTypes::Put.is_put() && !OptTypes::Put.is_call());
+        assert_eq!(OptTypes::Call.payoff(110.0, 100.0), 10.0);
+        assert_eq!(OptTypes::Call.payoff(90.0, 100.0), 0.0);
+        assert_eq!(OptTypes::Put.payoff(90.0, 100.0), 10.0);
+        assert_eq!(OptTypes::Put.payoff(110.0, 100.0), 0.0);
+        assert_eq!(OptTypes::Call.sign(), 1.0);
+        assert_eq!(OptTypes::Put.sign(), -1.0);
+    }
+
+    #[test]
+    fn annual_rho() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let opt_at = |annual_rate: f64| {
+            Options::new(
+                OptData::new(
+                    vec!["RHO".to_string(); 2],
+                    vec![OptTypes::Call, OptTypes::Put],
+                    vec![100.0; 2],
+                    vec![105.0; 2],
+                    vec![settle; 2],
+                    vec![maturity; 2],
+                    vec![0.01; 2],
+                    vec![annual_rate.ln_1p(); 2],
+                    vec![0.25; 2],
+                ),
+                Box::new(black_scholes::BlackScholesModel::new()),
+            )
+        };
+        let annual =
+            black_scholes::BlackScholesModel::new().with_rho_compounding(Compounding::Annual);
+        let greeks = annual.get_greeks(&opt_at(0.05));
+        let (up, down) = (
+            black_scholes::BlackScholesModel::new().get_price(&opt_at(0.0501)),
+            black_scholes::BlackScholesModel::new().get_price(&opt_at(0.0499)),
+        );
+        for i in 0..2 {
+            let fd = (up[i] - down[i]) / 0.0002 / 100.0;
+            assert!((greeks[i].rho - fd).abs() < 1e-6, "row {}", i);
+        }
+        let continuous = black_scholes::BlackScholesModel::new().get_greeks(&opt_at(0.05));
+        assert!((greeks[0].rho - continuous[0].rho / 1.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rate_day_count() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2022, 7, 1, 0, 0, 0).unwrap();
+        let opt_at = |rfr: f64, dividend: f64| {
+            Options::new(
+                OptData::new(
+                    vec!["DC".to_string(); 2],
+                    vec![OptTypes::Call, OptTypes::Put],
+                    vec![100.0; 2],
+                    vec![105.0; 2],
+                    vec![settle; 2],
+                    vec![maturity; 2],
+                    vec![dividend; 2],
+                    vec![rfr; 2],
+                    vec![0.25; 2],
+                ),
+                Box::new(black_scholes::BlackScholesModel::new()),
+            )
+        };
+        let bs = black_scholes::BlackScholesModel::new();
+        let act360 = bs.with_rate_day_count(DayCount::Act360);
+        let opt = opt_at(0.05, 0.01);
+        // Rates grow over 181 / 360 years, volatility over the duration
+        let scale = 181.0 / 360.0 / opt.opt_data.duration[0];
+        let expected = bs.get_price(&opt_at(0.05 * scale, 0.01 * scale));
+        let prices = act360.get_price(&opt);
+        for i in 0..2 {
+            assert!((prices[i] - expected[i]).abs() < 1e-12, "row {}", i);
+        }
+        let continuous = bs.with_rate_day_count(DayCount::Continuous);
+        assert_eq!(continuous.get_price(&opt), bs.get_price(&opt));
+
+        let greeks = act360.get_greeks(&opt);
+        let (up, down) = (
+            act360.get_price(&opt_at(0.0501, 0.01)),
+            act360.get_price(&opt_at(0.0499, 0.01)),
+        );
+        for i in 0..2 {
+            let fd = (up[i] - down[i]) / 0.0002 / 100.0;
+            assert!((greeks[i].rho - fd).abs() < 1e-6, "row {}", i);
+        }
+    }
+
+    #[test]
+    fn zero_volatility() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["PIN".to_string(); 3],
+                vec![OptTypes::Call, OptTypes::Put, OptTypes::Call],
+                vec![100.0; 3],
+                vec![90.0, 110.0, 110.0],
+                vec![settle; 3],
+                vec![maturity; 3],
+                vec![0.01; 3],
+                vec![0.03; 3],
+                vec![0.0; 3],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        opt.get_greeks();
+        let t = opt.opt_data.duration[0];
+        let spot = 100.0 * (-0.01 * t).exp();
+        let df = (-0.03 * t).exp();
+        assert!((opt.prices[0] - (spot - 90.0 * df)).abs() < 1e-9);
+        assert!((opt.prices[1] - (110.0 * df - spot)).abs() < 1e-9);
+        assert_eq!(opt.prices[2], 0.0);
+        assert!((opt.greeks[0].delta - (-0.01 * t).exp()).abs() < 1e-12);
+        assert!((opt.greeks[1].delta + (-0.01 * t).exp()).abs() < 1e-12);
+        assert_eq!(opt.greeks[2].delta, 0.0);
+        for g in &opt.greeks {
+            assert_eq!(g.gamma, 0.0);
+            assert_eq!(g.vega, 0.0);
+        }
+        // Theta per day and rho per 1% of the deterministic prices
+        let call_theta = (0.01 * spot - 0.03 * 90.0 * df) / DAYS_PER_YEAR;
+        let put_theta = (0.03 * 110.0 * df - 0.01 * spot) / DAYS_PER_YEAR;
+        assert!((opt.greeks[0].theta - call_theta).abs() < 1e-12);
+        assert!((opt.greeks[1].theta - put_theta).abs() < 1e-12);
+        assert!((opt.greeks[0].rho - t * 90.0 * df / 100.0).abs() < 1e-12);
+        assert!((opt.greeks[1].rho + t * 110.0 * df / 100.0).abs() < 1e-12);
+        assert_eq!(opt.greeks[2].theta, 0.0);
+        assert_eq!(opt.greeks[2].rho, 0.0);
+    }
+
+    #[test]
+    fn underlying_is_forward() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let spot_data = OptData::new(
+            vec!["ES".to_string(); 2],
+            vec![OptTypes::Call, OptTypes::Put],
+            vec![100.0; 2],
+            vec![105.0; 2],
+            vec![settle; 2],
+            vec![maturity; 2],
+            vec![0.01; 2],
+            vec![0.04; 2],
+            vec![0.2; 2],
+        );
+        let t = spot_data.duration[0];
+        let mut forward_data = spot_data.clone();
+        forward_data.underlying = vec![100.0 * ((0.04 - 0.01) * t).exp(); 2];
+        let mut spot = Options::new(spot_data, Box::new(black_scholes::BlackScholesModel::new()));
+        let mut forward = Options::new(
+            forward_data,
+            Box::new(black_scholes::BlackScholesModel::new().with_underlying_is_forward(true)),
+        );
+        spot.get_prices();
+        forward.get_prices();
+        forward.get_greeks();
+        for i in 0..2 {
+            assert!((spot.prices[i] - forward.prices[i]).abs() < 1e-9);
+            assert!((forward.greeks[i].rho + t * forward.prices[i] / 100.0).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn default() {
         let opt = Options::default();
@@ -60,12 +684,18 @@ mod test_options {
                 vec![OptTypes::Call],
                 vec![120.0],
                 vec![110.0],
-                vec![chrono::DateTime::from_utc(
-                    NaiveDate::from_ymd(2022, 9, 14).and_hms(2, 22, 0),
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 9, 14)
+                        .unwrap()
+                        .and_hms_opt(2, 22, 0)
+                        .unwrap(),
                     Utc,
                 )],
-                vec![chrono::DateTime::from_utc(
-                    NaiveDate::from_ymd(2022, 11, 18).and_hms(15, 0, 0),
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 11, 18)
+                        .unwrap()
+                        .and_hms_opt(15, 0, 0)
+                        .unwrap(),
                     Utc,
                 )],
                 vec![0.03],
@@ -76,9 +706,2588 @@ mod test_options {
         );
         opt.get_greeks();
         opt.get_prices();
-        let records = opt.to_records();
-        for rec in records {
+        let records = opt.to_records().unwrap();
+        for rec in &records {
             println!("{:?}", rec);
         }
+        let mut buf = Vec::new();
+        opt.write_csv_to(&mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("ticker,"));
+        assert_eq!(lines[1], records[0].join(","));
+
+        let mut buf = Vec::new();
+        opt.write_greeks_csv_to(&mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            csv.lines().next(),
+            Some("ticker,delta,gamma,vega,theta,rho")
+        );
+        opt.greeks.clear();
+        assert!(opt.write_greeks_csv_to(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn consistency() {
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string()],
+                vec![OptTypes::Call],
+                vec![120.0],
+                vec![110.0],
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 9, 14)
+                        .unwrap()
+                        .and_hms_opt(2, 22, 0)
+                        .unwrap(),
+                    Utc,
+                )],
+                vec![chrono::DateTime::from_naive_utc_and_offset(
+                    NaiveDate::from_ymd_opt(2022, 11, 18)
+                        .unwrap()
+                        .and_hms_opt(15, 0, 0)
+                        .unwrap(),
+                    Utc,
+                )],
+                vec![0.03],
+                vec![0.03],
+                vec![0.35],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        assert!(opt.is_consistent());
+        let err = opt.to_records().unwrap_err();
+        assert_eq!((err.name, err.found), ("prices", 0));
+        opt.get_prices();
+        opt.get_greeks();
+        opt.prices.push(1.0);
+        assert!(!opt.is_consistent());
+        let err = opt.to_records().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "prices has length 2, expected 1 (one per option)"
+        );
+    }
+
+    #[test]
+    fn clone_options() {
+        let mut opt = Options::new(chain().opt_data, Box::new(MonteCarloModel::new(1_000, 3)));
+        opt.get_prices();
+        let mut cloned = opt.clone();
+        assert_eq!(cloned.prices, opt.prices);
+        cloned.get_prices();
+        assert_eq!(cloned.prices, opt.prices);
+        // Selected rows keep the Monte Carlo model
+        let mut selected = opt.select(&[0]);
+        selected.get_prices();
+        assert_eq!(selected.prices[0], opt.prices[0]);
+    }
+
+    #[test]
+    fn warnings() {
+        let mut opt = chain();
+        opt.opt_data.strike[2] = 400.0;
+        opt.opt_data.duration[4] = 1e-4;
+        let mut warnings = Vec::new();
+        opt.get_prices_with_warnings(&mut warnings);
+        assert_eq!(opt.prices.len(), 7);
+        let flagged: Vec<(usize, WarningCategory)> =
+            warnings.iter().map(|w| (w.row, w.category)).collect();
+        assert_eq!(
+            flagged,
+            vec![
+                (2, WarningCategory::TinyVega),
+                (2, WarningCategory::ExtremeMoneyness),
+                (4, WarningCategory::TinyVega),
+                (4, WarningCategory::NearZeroDuration),
+            ]
+        );
+
+        // Vega comes from the stored greeks once computed
+        opt.get_greeks();
+        opt.greeks[0].vega = 0.0;
+        let tiny: Vec<usize> = opt
+            .warnings()
+            .iter()
+            .filter(|w| w.category == WarningCategory::TinyVega)
+            .map(|w| w.row)
+            .collect();
+        assert_eq!(tiny, vec![0, 2, 4]);
+        // Otherwise from the stored model, a deterministic model has no vega
+        let intrinsic = Options::new(chain().opt_data, Box::new(ForwardIntrinsicModel::new()));
+        assert!(intrinsic
+            .warnings()
+            .iter()
+            .all(|w| w.category == WarningCategory::TinyVega));
+        assert_eq!(intrinsic.warnings().len(), 7);
+    }
+
+    /// Prices every row at the model's fixed prices.
+    #[derive(Clone)]
+    struct FixedPrices(Vec<f64>);
+
+    impl PricingModel for FixedPrices {
+        fn get_price(&self, _opts: &Options) -> Vec<f64> {
+            self.0.clone()
+        }
+        fn get_greeks(&self, _opts: &Options) -> Vec<Greeks> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn price_floor() {
+        let raw = vec![1.0, -1e-13, 2.0, 0.0, -3e-12, 4.0, 5.0];
+        let mut opt = Options::new(chain().opt_data, Box::new(FixedPrices(raw.clone())));
+        let mut warnings = Vec::new();
+        opt.get_prices_with_warnings(&mut warnings);
+        assert_eq!(opt.prices, vec![1.0, 0.0, 2.0, 0.0, 0.0, 4.0, 5.0]);
+        let floored: Vec<usize> = warnings
+            .iter()
+            .filter(|w| w.category == WarningCategory::PriceFloored)
+            .map(|w| w.row)
+            .collect();
+        assert_eq!(floored, vec![1, 4]);
+        assert!(warnings.windows(2).all(|w| w[0].row <= w[1].row));
+
+        let mut raw_opt = Options::new(chain().opt_data, Box::new(FixedPrices(raw.clone())))
+            .with_price_floor(false);
+        raw_opt.get_prices();
+        assert_eq!(raw_opt.prices, raw);
+    }
+
+    #[test]
+    fn greeks_for_subset() {
+        let mut opt = chain();
+        opt.get_greeks_for(&[1, 4]);
+        let full = BlackScholesModel::new().get_greeks(&opt);
+        assert_eq!(opt.greeks.len(), 7);
+        assert_eq!(opt.greeks[1], full[1]);
+        assert_eq!(opt.greeks[4], full[4]);
+        assert_eq!(opt.greeks[0], Greeks::default());
+        // Previously computed rows are left untouched
+        opt.opt_data.volatility[1] = 0.5;
+        opt.get_greeks_for(&[4]);
+        assert_eq!(opt.greeks[1], full[1]);
+    }
+
+    #[test]
+    fn row_errors() {
+        let mut opt = chain();
+        opt.opt_data.strike[1] = -5.0;
+        opt.opt_data.volatility[4] = -0.2;
+        opt.get_prices();
+        assert_eq!(
+            opt.failed_rows(),
+            vec![
+                (
+                    1,
+                    PricingError::InvalidInput {
+                        field: "strike",
+                        value: -5.0
+                    }
+                ),
+                (
+                    4,
+                    PricingError::InvalidInput {
+                        field: "volatility",
+                        value: -0.2
+                    }
+                ),
+            ]
+        );
+        assert!(opt.prices[1].is_nan() && opt.prices[4].is_nan());
+        let good: Vec<usize> = (0..7).filter(|&i| opt.errors[i].is_none()).collect();
+        assert_eq!(good.len(), 5);
+        assert!(good.iter().all(|&i| opt.prices[i].is_finite()));
+        assert_eq!(opt.select(&good).failed_rows(), vec![]);
+    }
+
+    #[test]
+    fn into_iter_rows() {
+        let mut opt = chain();
+        opt.get_prices();
+        let (prices, strikes) = (opt.prices.clone(), opt.opt_data.strike.clone());
+        let rows: Vec<OptRow> = opt.into_iter().collect();
+        assert_eq!(rows.len(), 7);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.strike, strikes[i]);
+            assert_eq!(row.price, Some(prices[i]));
+            assert_eq!((row.greeks, row.implied_vol), (None, None));
+        }
+        assert_eq!(rows[1].opt_type, OptTypes::Put);
+        assert_eq!(rows[1].ticker, "AAPL");
+        let mut count = 0;
+        for row in chain() {
+            assert!(row.price.is_none());
+            count += 1;
+        }
+        assert_eq!(count, 7);
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Options>();
+        assert_send_sync::<BlackScholesModel>();
+        assert_send_sync::<CachingModel<CrrModel>>();
+
+        let opt = chain();
+        let expected = opt.compute().prices;
+        let ladders: Vec<Vec<f64>> = std::thread::scope(|s| {
+            let handles: Vec<_> = [-0.1, 0.0, 0.1]
+                .into_iter()
+                .map(|shift| {
+                    let opt = &opt;
+                    s.spawn(move || {
+                        (0..7)
+                            .map(|i| {
+                                opt.price_at(
+                                    i,
+                                    Overrides {
+                                        underlying: Some(
+                                            opt.opt_data.underlying[i] * (1.0 + shift),
+                                        ),
+                                        ..Overrides::default()
+                                    },
+                                )
+                                .unwrap()
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        for (a, b) in ladders[1].iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!(ladders[0][0] < ladders[1][0] && ladders[1][0] < ladders[2][0]);
+    }
+
+    #[test]
+    fn compute_without_mutation() {
+        let mut opt = chain();
+        let priced = opt.compute();
+        assert!(opt.prices.is_empty() && opt.greeks.is_empty());
+        assert_eq!(priced.inputs.tickers.len(), 7);
+        let (prices, greeks) = (priced.prices, priced.greeks);
+        opt.get_prices();
+        opt.get_greeks();
+        assert_eq!(prices, opt.prices);
+        assert_eq!(greeks, opt.greeks);
+    }
+
+    #[test]
+    fn set_model() {
+        let mut opt = chain();
+        opt.get_prices();
+        opt.get_greeks();
+        opt.set_model(Box::new(CrrModel::new(100)));
+        assert!(opt.prices.is_empty() && opt.greeks.is_empty());
+        opt.get_prices();
+        assert_eq!(opt.prices, CrrModel::new(100).get_price(&opt));
+    }
+
+    #[test]
+    fn clear_results() {
+        let mut opt = chain();
+        opt.get_prices();
+        opt.get_greeks();
+        opt.solve_implied_vols();
+        let prices = opt.prices.clone();
+        opt.clear_results();
+        assert!(opt.prices.is_empty() && opt.greeks.is_empty());
+        assert!(opt.implied_vol.is_empty() && opt.errors.is_empty());
+        assert!(opt.is_consistent());
+        assert_eq!(
+            opt.to_typed_records().unwrap_err(),
+            LengthError {
+                name: "prices",
+                expected: 7,
+                found: 0
+            }
+        );
+        assert!(opt.representative_iv().is_err());
+        opt.get_prices();
+        assert_eq!(opt.prices, prices);
+    }
+}
+
+#[cfg(test)]
+mod test_structures {
+    use crate::opt_data::OptData;
+    use crate::options_struct::{OptTypes, Options};
+    use crate::pricing_models::black_scholes;
+    use crate::structures::Structure;
+    use chrono::{NaiveDate, Utc};
+
+    #[test]
+    fn straddle() {
+        let settle = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 9, 14)
+                .unwrap()
+                .and_hms_opt(2, 22, 0)
+                .unwrap(),
+            Utc,
+        );
+        let maturity = chrono::DateTime::from_naive_utc_and_offset(
+            NaiveDate::from_ymd_opt(2022, 11, 18)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap(),
+            Utc,
+        );
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(), "AAPL".to_string()],
+                vec![OptTypes::Call, OptTypes::Put],
+                vec![120.0, 120.0],
+                vec![120.0, 120.0],
+                vec![settle, settle],
+                vec![maturity, maturity],
+                vec![0.0, 0.0],
+                vec![0.03, 0.03],
+                vec![0.35, 0.35],
+            ),
+            Box::new(black_scholes::BlackScholesModel::new()),
+        );
+        opt.get_prices();
+        opt.get_greeks();
+        let straddle = Structure::straddle(0, 1);
+        assert_eq!(
+            straddle.net_price(&opt).unwrap(),
+            opt.prices[0] + opt.prices[1]
+        );
+        let greeks = straddle.net_greeks(&opt).unwrap();
+        assert_eq!(greeks.delta, opt.greeks[0].delta + opt.greeks[1].delta);
+        assert_eq!(greeks.gamma, opt.greeks[0].gamma * 2.0);
+    }
+}
+
+#[cfg(test)]
+mod test_opt_data {
+    use crate::error::OptionsError;
+    use crate::opt_data::{
+        DateOrderError, Field, OptData, ParseConfig, ParseError, ParseMode, SourceTimezone,
+        DAYS_PER_YEAR,
+    };
+    use crate::options_struct::{LengthError, OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use chrono::{FixedOffset, TimeDelta, TimeZone, Utc};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn write_tmp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn from_dir() {
+        let dir = std::env::temp_dir().join("options_from_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let header = "ticker,opt_type,underlying,strike,settle,maturity,volatility\n";
+        for (name, strike) in [
+            ("chain_b.csv", 120.0),
+            ("chain_a.csv", 110.0),
+            ("other.csv", 1.0),
+        ] {
+            let row = format!("AAPL,call,120.0,{},2022-09-14,2022-11-18,0.35\n", strike);
+            std::fs::write(dir.join(name), format!("{}{}{}", header, row, row)).unwrap();
+        }
+
+        let data = OptData::from_dir(&dir, "chain_?.csv").unwrap();
+        assert_eq!(data.strike, vec![110.0, 110.0, 120.0, 120.0]);
+        assert_eq!(data.source[0].as_deref(), Some("chain_a.csv"));
+        assert_eq!(data.source[3].as_deref(), Some("chain_b.csv"));
+        assert!(OptData::from_dir(&dir, "*.json")
+            .unwrap()
+            .tickers
+            .is_empty());
+
+        std::fs::write(dir.join("chain_c.csv"), "ticker\nAAPL\n").unwrap();
+        match OptData::from_dir(&dir, "chain_*") {
+            Err(ParseError::File { path, error }) => {
+                assert!(path.ends_with("chain_c.csv"));
+                assert!(matches!(*error, ParseError::MissingColumn(_)));
+            }
+            _ => panic!("expected the error of chain_c.csv"),
+        }
+    }
+
+    #[test]
+    fn optional_columns() {
+        let path = write_tmp(
+            "options_optional_columns.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n",
+        );
+        let config = ParseConfig {
+            default_rfr: 0.03,
+            ..ParseConfig::default()
+        };
+        let opt_data = OptData::try_from_file(&path, &config).unwrap().0;
+        assert_eq!(opt_data.dividend, vec![0.0]);
+        assert_eq!(opt_data.rfr, vec![0.03]);
+
+        // Per row values win unless overridden
+        let path = write_tmp(
+            "options_override_rates.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,rfr,dividend,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.05,0.01,0.35\n",
+        );
+        let opt_data = OptData::try_from_file(&path, &config).unwrap().0;
+        assert_eq!((opt_data.rfr[0], opt_data.dividend[0]), (0.05, 0.01));
+        let config = ParseConfig {
+            override_rates: true,
+            ..config
+        };
+        let opt_data = OptData::try_from_file(&path, &config).unwrap().0;
+        assert_eq!((opt_data.rfr[0], opt_data.dividend[0]), (0.03, 0.0));
+    }
+
+    #[test]
+    fn sigma_header() {
+        let path = write_tmp(
+            "options_sigma_header.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,sigma\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n",
+        );
+        assert_eq!(
+            OptData::try_from_file(&path, &ParseConfig::default())
+                .unwrap()
+                .0
+                .volatility,
+            vec![0.35]
+        );
+    }
+
+    #[test]
+    fn percentage_inputs() {
+        let path = write_tmp(
+            "options_percentage_inputs.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,dividend,rfr,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,1.5%,3.0 %,35%\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.015,0.03,0.35\n",
+        );
+        let opt_data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(opt_data.dividend, vec![0.015, 0.015]);
+        assert_eq!(opt_data.rfr, vec![0.03, 0.03]);
+        assert_eq!(opt_data.volatility, vec![0.35, 0.35]);
+        let bad = write_tmp(
+            "options_percentage_bad.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,%\n",
+        );
+        assert!(OptData::try_from_file(&bad, &ParseConfig::default()).is_err());
+    }
+
+    #[test]
+    fn explicit_duration() {
+        let path = write_tmp(
+            "options_explicit_duration.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,duration\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,0.5\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,\n",
+        );
+        let opt_data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(opt_data.duration[0], 0.5);
+        assert!((opt_data.duration[1] - 65.0 / DAYS_PER_YEAR).abs() < 1e-12);
+
+        let swept = opt_data.clone().with_durations(vec![0.25, 1.0]).unwrap();
+        assert_eq!(swept.duration, vec![0.25, 1.0]);
+        assert!(matches!(
+            opt_data.clone().with_durations(vec![0.25]),
+            Err(OptionsError::Inconsistent(LengthError {
+                name: "duration",
+                expected: 2,
+                found: 1
+            }))
+        ));
+        match opt_data.with_durations(vec![0.25, -1.0]) {
+            Err(OptionsError::InvalidArgument(reason)) => {
+                assert_eq!(reason, "negative duration -1 at row 1")
+            }
+            _ => panic!("expected negative duration error"),
+        }
+        let bad = write_tmp(
+            "options_explicit_duration_bad.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,duration\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,-1\n",
+        );
+        assert!(OptData::try_from_file(&bad, &ParseConfig::default()).is_err());
+    }
+
+    #[test]
+    fn duplicates() {
+        let path = write_tmp(
+            "options_duplicates.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n\
+             AAPL,put,120.0,110.0,2022-09-14,2022-11-18,0.35\n\
+             AAPL,call,121.0,110.0,2022-09-15,2022-11-18,0.30\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-12-16,0.35\n",
+        );
+        assert_eq!(
+            OptData::try_from_file(&path, &ParseConfig::default())
+                .unwrap()
+                .0
+                .find_duplicates(),
+            vec![vec![0, 2]]
+        );
+        let config = ParseConfig {
+            reject_duplicates: true,
+            ..ParseConfig::default()
+        };
+        match OptData::try_from_file(&path, &config) {
+            Err(ParseError::Duplicate { lines }) => assert_eq!(lines, vec![2, 4]),
+            _ => panic!("expected duplicate error"),
+        }
+    }
+
+    #[test]
+    fn quoted_iv_consistency() {
+        let path = write_tmp(
+            "options_quoted_iv.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,quoted_iv\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,35.5%\n\
+             AAPL,call,120.0,115.0,2022-09-14,2022-11-18,0.35,0.30\n\
+             AAPL,call,120.0,120.0,2022-09-14,2022-11-18,0.35,\n",
+        );
+        let opt = Options::from_file_with(
+            &path,
+            Box::new(BlackScholesModel::new()),
+            &ParseConfig::default(),
+        )
+        .unwrap()
+        .0;
+        assert_eq!(opt.opt_data.quoted_iv, vec![Some(0.355), Some(0.30), None]);
+        assert_eq!(opt.vol_consistency(0.01), vec![1]);
+        assert_eq!(opt.vol_consistency(0.001), vec![0, 1]);
+    }
+
+    #[test]
+    fn headerless() {
+        let path = write_tmp(
+            "options_headerless.csv",
+            "AAPL,call,120.0,110.0,2022-09-14,2022-11-18,x,0.35\n\
+             AAPL,put,120.0,115.0,2022-09-14,2022-11-18,x,0.30\n",
+        );
+        let mut config = ParseConfig {
+            columns: Some(vec![
+                Field::Ticker,
+                Field::OptType,
+                Field::Underlying,
+                Field::Strike,
+                Field::Settle,
+                Field::Maturity,
+                Field::Ignore,
+                Field::Volatility,
+            ]),
+            ..ParseConfig::default()
+        };
+        let (opt_data, _) = OptData::try_from_file(&path, &config).unwrap();
+        assert_eq!(opt_data.opt_types, vec![OptTypes::Call, OptTypes::Put]);
+        assert_eq!(opt_data.volatility, vec![0.35, 0.30]);
+
+        config.columns.as_mut().unwrap().remove(3);
+        match OptData::try_from_file(&path, &config) {
+            Err(ParseError::MissingColumn(name)) => assert_eq!(name, "strike"),
+            _ => panic!("expected missing strike"),
+        }
+    }
+
+    #[test]
+    fn header_only() {
+        let path = write_tmp(
+            "options_header_only.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n",
+        );
+        let (opt_data, skipped) = OptData::try_from_file(&path, &ParseConfig::default()).unwrap();
+        assert!(opt_data.tickers.is_empty() && skipped.is_empty());
+
+        let config = ParseConfig {
+            max_wait: Duration::from_millis(100),
+            ..ParseConfig::default()
+        };
+        let start = Instant::now();
+        let (opt_data, _) = OptData::try_from_file(&path, &config).unwrap();
+        assert!(opt_data.tickers.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn lenient_mode() {
+        let path = write_tmp(
+            "options_lenient_mode.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,dividend,rfr,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0,0.03,0.35\n\
+             AAPL,straddle,120.0,110.0,2022-09-14,2022-11-18,0,0.03,0.35\n\
+             AAPL,put,abc,110.0,2022-09-14,2022-11-18,0,0.03,0.35\n\
+             AAPL,put,120.0,110.0,2022-09-14,2022-11-18,0,0.03,0.35\n",
+        );
+        let config = ParseConfig {
+            mode: ParseMode::Lenient,
+            ..ParseConfig::default()
+        };
+        let (opt_data, skipped) = OptData::try_from_file(&path, &config).unwrap();
+        assert_eq!(opt_data.tickers.len(), 2);
+        assert_eq!(opt_data.volatility.len(), 2);
+        assert_eq!(
+            skipped.iter().map(|s| s.0).collect::<Vec<usize>>(),
+            vec![3, 4]
+        );
+
+        match OptData::try_from_file(&path, &ParseConfig::default()) {
+            Err(ParseError::Row { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("expected row error"),
+        }
+    }
+
+    #[test]
+    fn date_error_context() {
+        let path = write_tmp(
+            "options_date_error_context.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-13-18,0.35\n",
+        );
+        match OptData::try_from_file(&path, &ParseConfig::default()) {
+            Err(ParseError::Date {
+                line, field, value, ..
+            }) => {
+                assert_eq!(
+                    (line, field.as_str(), value.as_str()),
+                    (3, "maturity", "2022-13-18")
+                )
+            }
+            _ => panic!("expected date error"),
+        }
+    }
+
+    #[test]
+    fn contract_specs() {
+        let path = write_tmp(
+            "options_contract_specs.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,multiplier,currency\n\
+             ES,call,4000.0,4000.0,2022-09-14,2022-11-18,0.2,50,USD\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,100,USD\n",
+        );
+        let opt_data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(opt_data.multiplier, vec![50.0, 100.0]);
+        let mut opt = Options::new(opt_data, Box::new(BlackScholesModel::new()));
+        opt.get_prices();
+        let net = opt.net_premium(&[1.0, -2.0]).unwrap();
+        assert_eq!(net.keys().collect::<Vec<_>>(), vec!["USD"]);
+        assert!((net["USD"] - (50.0 * opt.prices[0] - 200.0 * opt.prices[1])).abs() < 1e-9);
+        opt.opt_data.currency[1] = "EUR".to_string();
+        let net = opt.net_premium(&[1.0, -2.0]).unwrap();
+        assert!((net["USD"] - 50.0 * opt.prices[0]).abs() < 1e-9);
+        assert!((net["EUR"] + 200.0 * opt.prices[1]).abs() < 1e-9);
+        assert_eq!(
+            opt.net_premium(&[1.0]),
+            Err(LengthError {
+                name: "quantities",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn source_timezone() {
+        let path = write_tmp(
+            "options_source_timezone.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-11-18 09:30:00,2022-11-18T16:00:00+00:00,0.35\n",
+        );
+        let config = ParseConfig {
+            timezone: SourceTimezone::Fixed(FixedOffset::west_opt(5 * 3600).unwrap()),
+            ..ParseConfig::default()
+        };
+        let opt_data = OptData::try_from_file(&path, &config).unwrap().0;
+        // Naive settle is shifted, the explicit offset of maturity is kept
+        assert_eq!(
+            opt_data.settles[0],
+            Utc.with_ymd_and_hms(2022, 11, 18, 14, 30, 0).unwrap()
+        );
+        assert_eq!(
+            opt_data.maturities[0],
+            Utc.with_ymd_and_hms(2022, 11, 18, 16, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn source_timezone_named_across_dst() {
+        // New York leaves daylight saving on 2022-11-06, UTC-4 before and UTC-5 after
+        let path = write_tmp(
+            "options_source_timezone_named.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-11-04 09:30:00,2022-11-07 16:00:00,0.35\n",
+        );
+        let config = ParseConfig {
+            timezone: SourceTimezone::Named(chrono_tz::America::New_York),
+            ..ParseConfig::default()
+        };
+        let opt_data = OptData::try_from_file(&path, &config).unwrap().0;
+        assert_eq!(
+            opt_data.settles[0],
+            Utc.with_ymd_and_hms(2022, 11, 4, 13, 30, 0).unwrap()
+        );
+        assert_eq!(
+            opt_data.maturities[0],
+            Utc.with_ymd_and_hms(2022, 11, 7, 21, 0, 0).unwrap()
+        );
+        // The extra hour of the transition is part of the duration
+        let hours = 3.0 * 24.0 + 7.5;
+        assert!((opt_data.duration[0] - hours / 24.0 / DAYS_PER_YEAR).abs() < 1e-12);
+    }
+
+    #[test]
+    fn bid_ask_edge() {
+        let path = write_tmp(
+            "options_bid_ask_edge.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,bid,ask\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,13.0,13.4\n\
+             AAPL,call,120.0,115.0,2022-09-14,2022-11-18,0.35,1.0,1.2\n\
+             AAPL,call,120.0,120.0,2022-09-14,2022-11-18,0.35,,6.0\n",
+        );
+        let opt_data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(opt_data.bid, vec![Some(13.0), Some(1.0), None]);
+        let mut opt = Options::new(opt_data, Box::new(BlackScholesModel::new()));
+        opt.get_prices();
+        let edge = opt.edge().unwrap();
+        let first = edge[0].unwrap();
+        assert_eq!(first.mid, 13.2);
+        assert!((first.edge - (opt.prices[0] - 13.2)).abs() < 1e-12);
+        assert_eq!(
+            first.outside_spread,
+            opt.prices[0] < 13.0 || opt.prices[0] > 13.4
+        );
+        assert!(edge[1].unwrap().outside_spread);
+        assert_eq!(edge[2], None);
+    }
+
+    #[test]
+    fn solve_implied_vols_on_load() {
+        let path = write_tmp(
+            "options_solve_on_load.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility,market_price\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35,14.0\n\
+             AAPL,call,120.0,115.0,2022-09-14,2022-11-18,0.35,\n",
+        );
+        let config = ParseConfig {
+            solve_implied_vols: true,
+            ..ParseConfig::default()
+        };
+        let (opt, skipped) =
+            Options::from_file_with(&path, Box::new(BlackScholesModel::new()), &config).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(opt.opt_data.volatility, vec![0.35, 0.35]);
+        assert!(opt.implied_vol[0] > 0.0 && opt.implied_vol[0] != 0.35);
+        assert!(opt.implied_vol[1].is_nan());
+        let (lazy, _) = Options::from_file_with(
+            &path,
+            Box::new(BlackScholesModel::new()),
+            &ParseConfig::default(),
+        )
+        .unwrap();
+        assert!(lazy.implied_vol.is_empty());
+    }
+
+    #[test]
+    fn sub_second_duration() {
+        let settle = Utc.with_ymd_and_hms(2022, 11, 18, 15, 0, 0).unwrap();
+        let maturity = settle + TimeDelta::milliseconds(500);
+        let opt_data = OptData::new(
+            vec!["SPX".to_string()],
+            vec![OptTypes::Call],
+            vec![4000.0],
+            vec![4000.0],
+            vec![settle],
+            vec![maturity],
+            vec![0.0],
+            vec![0.03],
+            vec![0.2],
+        );
+        assert!((opt_data.duration[0] * 60.0 * 60.0 * 24.0 * DAYS_PER_YEAR - 0.5).abs() < 1e-9)
+    }
+
+    #[test]
+    fn negative_duration() {
+        let settle = Utc.with_ymd_and_hms(2022, 11, 18, 15, 0, 0).unwrap();
+        let columns = || {
+            (
+                vec!["SPX".to_string()],
+                vec![OptTypes::Call],
+                vec![4000.0],
+                vec![4000.0],
+                vec![settle],
+                vec![settle - TimeDelta::days(1)],
+                vec![0.0],
+                vec![0.03],
+                vec![0.2],
+            )
+        };
+        let (t, o, u, k, s, m, q, r, v) = columns();
+        let opt_data = OptData::new(t, o, u, k, s, m, q, r, v);
+        assert!((opt_data.duration[0] + 1.0 / DAYS_PER_YEAR).abs() < 1e-12);
+        let (t, o, u, k, s, m, q, r, v) = columns();
+        match OptData::try_new(t, o, u, k, s, m, q, r, v) {
+            Err(errors) => assert_eq!(errors[0].row, 0),
+            Ok(_) => panic!("expected date order error"),
+        }
+    }
+
+    #[test]
+    fn valuation_time() {
+        let settle = Utc.with_ymd_and_hms(2022, 9, 14, 0, 0, 0).unwrap();
+        let valuation = Utc.with_ymd_and_hms(2022, 9, 16, 20, 0, 0).unwrap();
+        let maturities = vec![
+            Utc.with_ymd_and_hms(2022, 11, 18, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2022, 12, 16, 0, 0, 0).unwrap(),
+        ];
+        let opt_data = OptData::new(
+            vec!["AAPL".to_string(); 2],
+            vec![OptTypes::Call; 2],
+            vec![120.0; 2],
+            vec![110.0; 2],
+            vec![settle, settle - TimeDelta::days(3)],
+            maturities.clone(),
+            vec![0.0; 2],
+            vec![0.03; 2],
+            vec![0.35; 2],
+        )
+        .with_durations(vec![1.0, 1.0])
+        .unwrap()
+        .with_valuation_time(valuation)
+        .unwrap();
+        assert_eq!(opt_data.settles, vec![valuation; 2]);
+        for (duration, maturity) in opt_data.duration.iter().zip(maturities) {
+            let days = (maturity - valuation).num_seconds() as f64 / 86_400.0;
+            assert!((duration - days / DAYS_PER_YEAR).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn valuation_after_maturity() {
+        let settle = Utc.with_ymd_and_hms(2022, 9, 14, 0, 0, 0).unwrap();
+        let valued = OptData::new(
+            vec!["AAPL".to_string()],
+            vec![OptTypes::Call],
+            vec![120.0],
+            vec![110.0],
+            vec![settle],
+            vec![settle + TimeDelta::days(1)],
+            vec![0.0],
+            vec![0.03],
+            vec![0.35],
+        )
+        .with_valuation_time(settle + TimeDelta::days(2));
+        assert!(matches!(
+            valued,
+            Err(OptionsError::DateOrder(DateOrderError { row: 0, .. }))
+        ));
+    }
+
+    #[test]
+    fn date_order() {
+        let settle = Utc.with_ymd_and_hms(2022, 9, 14, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2022, 11, 18, 0, 0, 0).unwrap();
+        let mut opt_data = OptData::new(
+            vec!["AAPL".to_string(); 3],
+            vec![OptTypes::Call; 3],
+            vec![120.0; 3],
+            vec![110.0; 3],
+            vec![settle; 3],
+            vec![maturity; 3],
+            vec![0.0; 3],
+            vec![0.03; 3],
+            vec![0.35; 3],
+        );
+        assert_eq!(opt_data.check_date_order(), Ok(()));
+        // A join swapping the dates of a row
+        opt_data.settles[1] = maturity;
+        opt_data.maturities[1] = settle;
+        let errors = opt_data.check_date_order().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![DateOrderError {
+                row: 1,
+                settle: maturity,
+                maturity: settle,
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "row 1: maturity 2022-09-14 00:00:00 UTC is before settle 2022-11-18 00:00:00 UTC"
+        );
+
+        let path = write_tmp(
+            "options_date_order.csv",
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-11-18,2022-09-14,0.35\n",
+        );
+        match OptData::try_from_file(&path, &ParseConfig::default()) {
+            Err(ParseError::Row { line: 2, reason }) => assert!(reason.contains("2022-09-14")),
+            _ => panic!("expected row error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_monte_carlo {
+    use super::fixtures::chain;
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::monte_carlo::MonteCarloModel;
+    use crate::pricing_models::PricingModel;
+    use crate::utilities::chunk_opt;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn seeded_reproducible() {
+        let opt = chain();
+        let first = MonteCarloModel::new(2_000, 42).get_price(&opt);
+        let second = MonteCarloModel::new(2_000, 42).get_price(&opt);
+        assert_eq!(first, second);
+        let other = MonteCarloModel::new(2_000, 43).get_price(&opt);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn thread_count_independent() {
+        let opt = chain();
+        let single = MonteCarloModel::new(2_000, 7).get_price(&opt);
+        for threads in [2, 3, 8] {
+            let multi = MonteCarloModel::new(2_000, 7)
+                .with_threads(threads)
+                .get_price(&opt);
+            assert_eq!(single, multi);
+        }
+        let greeks = MonteCarloModel::new(500, 7).get_greeks(&opt);
+        let greeks_multi = MonteCarloModel::new(500, 7)
+            .with_threads(4)
+            .get_greeks(&opt);
+        assert_eq!(greeks, greeks_multi);
+    }
+
+    #[test]
+    fn row_order_independent() {
+        let opt = chain();
+        let model = MonteCarloModel::new(1_000, 5);
+        let full = model.get_price(&opt);
+        let reversed = opt.select(&[6, 5, 4, 3, 2, 1, 0]);
+        let mut prices = model.get_price(&reversed);
+        prices.reverse();
+        assert_eq!(prices, full);
+        for (chunk, start) in chunk_opt(opt.clone(), 3).iter().zip([0, 3, 6]) {
+            let prices = model.get_price(chunk);
+            assert_eq!(prices[..], full[start..start + prices.len()]);
+        }
+    }
+
+    #[test]
+    fn injected_rng() {
+        let opt = chain();
+        let a = MonteCarloModel::<StdRng>::with_rng(1_000, 1).get_price(&opt);
+        let b = MonteCarloModel::<StdRng>::with_rng(1_000, 1)
+            .with_threads(3)
+            .get_price(&opt);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn converges_to_black_scholes() {
+        let opt = chain();
+        let mc = MonteCarloModel::new(100_000, 11).get_price(&opt);
+        let bs = BlackScholesModel::new().get_price(&opt);
+        for (m, b) in mc.iter().zip(bs.iter()) {
+            assert!((m - b).abs() < 0.25, "{} vs {}", m, b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_models {
+    use super::fixtures::chain;
+    use crate::error::OptionsError;
+    use crate::greeks::Greeks;
+    use crate::options_struct::{LengthError, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::caching::CachingModel;
+    use crate::pricing_models::crr::CrrModel;
+    use crate::pricing_models::forward_intrinsic::ForwardIntrinsicModel;
+    use crate::pricing_models::kind::{build_model, ModelKind, ModelParams, UnknownModelError};
+    use crate::pricing_models::monte_carlo::MonteCarloModel;
+    use crate::pricing_models::shifted_vol::ShiftedVolModel;
+    use crate::pricing_models::PricingModel;
+
+    #[test]
+    fn vegas_match_greeks() {
+        let opt = chain();
+        let model = BlackScholesModel::new();
+        let vegas = model.vegas(&opt);
+        let greeks = model.get_greeks(&opt);
+        for (v, g) in vegas.iter().zip(greeks.iter()) {
+            assert_eq!(*v, g.vega);
+        }
+    }
+
+    #[test]
+    fn caching_model() {
+        let opt = chain();
+        let caching = CachingModel::new(CrrModel::new(100));
+        let first = caching.get_price(&opt);
+        assert_eq!(first, CrrModel::new(100).get_price(&opt));
+        assert_eq!(caching.len(), (7, 0));
+        // Cached rows are reused, a changed row is computed
+        let mut changed = chain();
+        changed.opt_data.underlying[0] = 125.0;
+        let second = caching.get_price(&changed);
+        assert_eq!(caching.len(), (8, 0));
+        assert_eq!(second[1..], first[1..]);
+        assert_ne!(second[0], first[0]);
+        caching.clear();
+        assert!(caching.is_empty());
+    }
+
+    /// Prices only the first row of any chain.
+    #[derive(Clone)]
+    struct FirstRowOnly;
+
+    impl PricingModel for FirstRowOnly {
+        fn get_price(&self, opts: &Options) -> Vec<f64> {
+            BlackScholesModel::new().get_price(opts)[..1].to_vec()
+        }
+        fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+            BlackScholesModel::new().get_greeks(opts)[..1].to_vec()
+        }
+    }
+
+    #[test]
+    fn caching_model_shortfall() {
+        let caching = CachingModel::new(FirstRowOnly);
+        let prices = caching.get_price(&chain());
+        assert_eq!(prices.len(), 1);
+        assert_eq!(caching.len(), (1, 0));
+        let mut opt = Options::new(chain().opt_data, Box::new(caching));
+        assert!(matches!(
+            opt.try_price(),
+            Err(OptionsError::Inconsistent(LengthError {
+                name: "prices",
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn caching_model_per_contract() {
+        // Two tickers with identical inputs draw different Monte Carlo paths,
+        // the cache must not hand one the price of the other
+        let mut opt = chain().select(&[0, 0]);
+        opt.opt_data.tickers[1] = "MSFT".to_string();
+        let direct = MonteCarloModel::new(1_000, 11).get_price(&opt);
+        assert_ne!(direct[0], direct[1]);
+        let forward = CachingModel::new(MonteCarloModel::new(1_000, 11)).get_price(&opt);
+        let reversed =
+            CachingModel::new(MonteCarloModel::new(1_000, 11)).get_price(&opt.select(&[1, 0]));
+        assert_eq!(forward, direct);
+        assert_eq!(reversed, vec![direct[1], direct[0]]);
+    }
+
+    #[test]
+    fn shifted_vol_model() {
+        let opt = chain();
+        let shifted = ShiftedVolModel::new(BlackScholesModel::new(), 0.02, -0.5);
+        let expected: Vec<f64> = (0..7)
+            .map(|i| 0.35 + 0.02 - 0.5 * ((105.0 + 5.0 * i as f64) / 120.0).ln())
+            .collect();
+        let prices = shifted.get_price(&opt);
+        assert_eq!(prices, opt.price_with_vols(&expected).unwrap());
+        // Low strikes are stressed more with a negative slope
+        assert!(shifted.shifted_vol(0.35, 120.0, 105.0) > shifted.shifted_vol(0.35, 120.0, 135.0));
+        // A flat stress is a parallel shift
+        let flat = ShiftedVolModel::new(BlackScholesModel::new(), 0.02, 0.0);
+        // Vega is per vol point
+        let vega = BlackScholesModel::new().get_greeks(&opt)[3].vega;
+        let diff = flat.get_price(&opt)[3] - BlackScholesModel::new().get_price(&opt)[3];
+        assert!((diff - 2.0 * vega).abs() < 1e-2 * diff.abs());
+        // Shifts below zero vol are clamped, pricing the deterministic value
+        let crushed = ShiftedVolModel::new(BlackScholesModel::new(), -0.5, 0.0);
+        assert_eq!(crushed.shifted_vol(0.35, 120.0, 105.0), 0.0);
+        assert_eq!(
+            crushed.get_price(&opt),
+            opt.price_with_vols(&[0.0; 7]).unwrap()
+        );
+        assert!(crushed
+            .get_price(&opt)
+            .iter()
+            .all(|price| price.is_finite()));
+    }
+
+    #[test]
+    fn model_kind() {
+        for kind in ModelKind::ALL {
+            assert_eq!(kind.to_string().parse::<ModelKind>(), Ok(kind));
+        }
+        assert_eq!(
+            " Black-Scholes".parse::<ModelKind>(),
+            Ok(ModelKind::BlackScholes)
+        );
+        let err = "bachelier".parse::<ModelKind>().unwrap_err();
+        assert_eq!(err, UnknownModelError("bachelier".to_string()));
+        assert!(err.to_string().contains("black-scholes, black76, crr"));
+
+        let opt = chain();
+        let params = ModelParams {
+            steps: 50,
+            ..ModelParams::default()
+        };
+        assert_eq!(
+            build_model(ModelKind::BlackScholes, params).get_price(&opt),
+            BlackScholesModel::new().get_price(&opt)
+        );
+        assert_eq!(
+            build_model(ModelKind::Crr, params).get_price(&opt),
+            CrrModel::new(50).get_price(&opt)
+        );
+    }
+
+    #[test]
+    fn forward_intrinsic_floor() {
+        let mut opt = chain();
+        opt.get_prices();
+        opt.get_greeks();
+        let floor = ForwardIntrinsicModel::new();
+        let intrinsic = floor.get_price(&opt);
+        assert!(opt.prices.iter().zip(&intrinsic).all(|(p, i)| p >= i));
+
+        // Matches Black-Scholes at zero volatility
+        opt.opt_data.volatility = vec![0.0; 7];
+        opt.get_prices();
+        opt.get_greeks();
+        let greeks = floor.get_greeks(&opt);
+        for i in 0..7 {
+            assert!((opt.prices[i] - intrinsic[i]).abs() < 1e-9);
+            assert!((opt.greeks[i].delta - greeks[i].delta).abs() < 1e-12);
+            assert!((opt.greeks[i].rho - greeks[i].rho).abs() < 1e-9);
+            assert!((opt.greeks[i].theta - greeks[i].theta).abs() < 1e-9);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_risk {
+    use super::fixtures::chain;
+    use crate::calendar::DayCount;
+    use crate::error::OptionsError;
+    use crate::greeks::{GreekKind, Greeks};
+    use crate::opt_data::DAYS_PER_YEAR;
+    use crate::options_struct::{ChainDiff, LengthError, Moneyness, OptTypes, Overrides};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::normal::FAST_CDF_MAX_ERROR;
+    use crate::pricing_models::PricingModel;
+
+    #[test]
+    fn summary() {
+        let mut opt = chain();
+        assert_eq!(opt.summary().mean_price, None);
+        opt.get_prices();
+        opt.get_greeks();
+        opt.prices[6] = f64::NAN;
+        let summary = opt.summary();
+        assert_eq!((summary.count, summary.calls, summary.puts), (7, 4, 3));
+        assert_eq!(summary.non_finite, 1);
+        let prices = &opt.prices[..6];
+        assert_eq!(summary.max_price, prices.iter().copied().reduce(f64::max));
+        assert!((summary.mean_price.unwrap() - prices.iter().sum::<f64>() / 6.0).abs() < 1e-12);
+        assert_eq!(summary.mean_implied_vol, None);
+        assert!((summary.mean_volatility.unwrap() - 0.35).abs() < 1e-12);
+        assert!(summary.to_string().contains("7 (4 calls, 3 puts)"));
+    }
+
+    #[test]
+    fn spot_repricer() {
+        let mut opt = chain();
+        let repricer = BlackScholesModel::new().spot_repricer(&opt);
+        assert_eq!(repricer.len(), 7);
+        let spots = [100.0, 120.0, 131.5];
+        let curve = BlackScholesModel::new().price_curve(&opt, 3, &spots);
+        for (spot, on_curve) in spots.iter().zip(curve.iter()) {
+            opt.opt_data.underlying = vec![*spot; 7];
+            let full = BlackScholesModel::new().get_price(&opt);
+            for (a, b) in repricer
+                .prices(&[*spot; 7])
+                .unwrap()
+                .iter()
+                .zip(full.iter())
+            {
+                assert!((a - b).abs() < 1e-10, "{} vs {}", a, b);
+            }
+            assert!((on_curve - full[3]).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn moneyness_class() {
+        // Spot 120, strikes 105 to 135, calls on even rows
+        let classes = chain().moneyness_class(0.02);
+        assert_eq!(
+            classes,
+            vec![
+                Moneyness::InTheMoney,
+                Moneyness::OutOfTheMoney,
+                Moneyness::InTheMoney,
+                Moneyness::AtTheMoney,
+                Moneyness::OutOfTheMoney,
+                Moneyness::InTheMoney,
+                Moneyness::OutOfTheMoney,
+            ]
+        );
+        // A wider band pulls the neighbouring strikes in
+        assert_eq!(
+            chain().moneyness_class(0.05)[2..5],
+            [Moneyness::AtTheMoney; 3]
+        );
+    }
+
+    #[test]
+    fn age() {
+        let mut opt = chain();
+        opt.get_prices();
+        let aged = opt.age(3.0);
+        for i in 0..7 {
+            let dur = opt.opt_data.duration[i] - 3.0 / DAYS_PER_YEAR;
+            assert!((aged.opt_data.duration[i] - dur).abs() < 1e-12);
+            let expected = opt
+                .price_at(
+                    i,
+                    Overrides {
+                        duration: Some(dur),
+                        ..Overrides::default()
+                    },
+                )
+                .unwrap();
+            assert!((aged.prices[i] - expected).abs() < 1e-12);
+        }
+        assert_eq!(aged.greeks.len(), 7);
+        assert_eq!(opt.age(1000.0).opt_data.duration, vec![0.0; 7]);
+        assert_eq!(opt.age(1000.0).opt_data.settles, opt.opt_data.maturities);
+        // Expired rows are worth their intrinsic value, also at the money
+        opt.opt_data.strike[3] = opt.opt_data.underlying[3];
+        let expired = opt.age(1000.0);
+        assert!(expired.errors.iter().all(Option::is_none));
+        for i in 0..7 {
+            let (spot, strike) = (opt.opt_data.underlying[i], opt.opt_data.strike[i]);
+            let intrinsic = match opt.opt_data.opt_types[i] {
+                OptTypes::Call => (spot - strike).max(0.0),
+                OptTypes::Put => (strike - spot).max(0.0),
+            };
+            assert_eq!(expired.prices[i], intrinsic, "row {}", i);
+            assert!(expired.greeks[i].gamma.is_finite(), "row {}", i);
+        }
+    }
+
+    #[test]
+    fn out_of_range_rows() {
+        let mut opt = chain();
+        opt.get_greeks();
+        assert_eq!(opt.price_at(7, Overrides::default()), None);
+        let targets = (GreekKind::Delta, GreekKind::Gamma);
+        assert_eq!(opt.hedge_two(&[1.0; 7], (0, 7), targets), Ok(None));
+        assert!(opt.hedge_two(&[1.0; 7], (0, 1), targets).unwrap().is_some());
+        let repricer = BlackScholesModel::new().spot_repricer(&opt);
+        assert_eq!(
+            repricer.prices(&[120.0; 3]),
+            Err(LengthError {
+                name: "underlying",
+                expected: 7,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn greek_buckets() {
+        let mut opt = chain();
+        opt.get_greeks();
+        // Spot 120 against strikes 105..135, all rows share one tenor
+        let buckets = opt.greek_buckets(&[0.95, 1.05], &[0.1]).unwrap();
+        assert_eq!(
+            buckets.keys().copied().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 1), (2, 1)]
+        );
+        let mut high = opt.greeks[0];
+        high += opt.greeks[1];
+        assert_eq!(buckets[&(2, 1)], high);
+        let total = buckets.values().fold(Greeks::default(), |mut acc, g| {
+            acc += *g;
+            acc
+        });
+        assert!((total.delta - opt.portfolio_greeks(&[1.0; 7]).unwrap().delta).abs() < 1e-12);
+        assert_eq!(
+            opt.portfolio_greeks(&[1.0; 3]),
+            Err(LengthError {
+                name: "quantities",
+                expected: 7,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn diff_chains() {
+        let mut a = chain();
+        a.get_prices();
+        a.get_greeks();
+        // Reversed, without row 0, one volatility bumped and a new contract
+        let mut b = a.select(&[6, 5, 4, 3, 2, 1]);
+        b.opt_data.volatility[0] += 0.01;
+        b.opt_data.strike[5] = 200.0;
+        b.get_prices();
+        b.get_greeks();
+        let diffs = crate::options_struct::diff_chains(&a, &b).unwrap();
+        assert_eq!(diffs.len(), 8);
+        let moved: Vec<usize> = diffs
+            .iter()
+            .filter_map(|d| match d {
+                ChainDiff::Matched { row_a, price, .. } if *price != 0.0 => Some(*row_a),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(moved, vec![6]);
+        match &diffs[6] {
+            ChainDiff::Matched {
+                row_b: 0, greeks, ..
+            } => assert!(greeks.unwrap().vega.abs() > 0.0),
+            d => panic!("unexpected {:?}", d),
+        }
+        assert!(matches!(diffs[0], ChainDiff::OnlyInA { row: 0, .. }));
+        assert!(matches!(diffs[1], ChainDiff::OnlyInA { row: 1, .. }));
+        match &diffs[7] {
+            ChainDiff::OnlyInB { key, row: 5 } => assert_eq!(key.strike, 200.0),
+            d => panic!("unexpected {:?}", d),
+        }
+        // Signed zero strikes match, NaN strikes are rejected
+        let mut zero_a = a.select(&[0]);
+        let mut zero_b = a.select(&[0]);
+        zero_a.opt_data.strike[0] = 0.0;
+        zero_b.opt_data.strike[0] = -0.0;
+        let diffs = crate::options_struct::diff_chains(&zero_a, &zero_b).unwrap();
+        assert!(matches!(diffs[..], [ChainDiff::Matched { .. }]));
+        zero_b.opt_data.strike[0] = f64::NAN;
+        assert!(matches!(
+            crate::options_struct::diff_chains(&zero_a, &zero_b),
+            Err(OptionsError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn price_with_vols() {
+        let mut opt = chain();
+        opt.get_prices();
+        let vols: Vec<f64> = (0..7).map(|i| 0.2 + 0.01 * i as f64).collect();
+        let prices = opt.price_with_vols(&vols).unwrap();
+        assert_eq!(opt.opt_data.volatility, vec![0.35; 7]);
+        for (i, price) in prices.iter().enumerate() {
+            let expected = opt
+                .price_at(
+                    i,
+                    Overrides {
+                        volatility: Some(vols[i]),
+                        ..Overrides::default()
+                    },
+                )
+                .unwrap();
+            assert!((price - expected).abs() < 1e-12, "row {}", i);
+            assert!(*price < opt.prices[i]);
+        }
+        let err = opt.price_with_vols(&vols[..3]).unwrap_err();
+        assert_eq!((err.name, err.expected, err.found), ("vols", 7, 3));
+    }
+
+    #[test]
+    fn atm_vol_and_skew() {
+        let mut opt = chain();
+        let d = &opt.opt_data;
+        // A smile linear in log forward moneyness is recovered exactly
+        opt.implied_vol = (0..7)
+            .map(|i| {
+                let forward = d.underlying[i] * ((d.rfr[i] - d.dividend[i]) * d.duration[i]).exp();
+                0.3 - 0.2 * (d.strike[i] / forward).ln()
+            })
+            .collect();
+        let surface = opt.atm_vol_and_skew().unwrap();
+        assert_eq!(surface.len(), 1);
+        assert_eq!(surface[0].ticker, "AAPL");
+        assert!((surface[0].atm_vol - 0.3).abs() < 1e-12);
+        assert!((surface[0].skew + 0.2).abs() < 1e-12);
+        // Unsolved rows are skipped, a single strike is not enough
+        opt.implied_vol = vec![f64::NAN; 7];
+        opt.implied_vol[2] = 0.3;
+        assert!(opt.atm_vol_and_skew().unwrap().is_empty());
+    }
+
+    #[test]
+    fn scenario_rate() {
+        let mut opt = chain();
+        opt.get_prices();
+        opt.get_greeks();
+        let ladder = opt.scenario_rate(&[-0.0001, 0.0, 0.01]);
+        assert_eq!(ladder.len(), 3);
+        assert!(ladder[1].iter().all(|change| *change == 0.0));
+        for (row, (down, up)) in ladder[0].iter().zip(&ladder[2]).enumerate() {
+            // A 1bp move is about a hundredth of rho, which is per 1% rate change
+            let expected = -opt.greeks[row].rho / 100.0;
+            assert!(
+                (down - expected).abs() < 1e-2 * expected.abs(),
+                "row {}",
+                row
+            );
+            let bumped = opt
+                .price_at(
+                    row,
+                    Overrides {
+                        rfr: Some(opt.opt_data.rfr[row] + 0.01),
+                        ..Overrides::default()
+                    },
+                )
+                .unwrap();
+            assert!((up - (bumped - opt.prices[row])).abs() < 1e-9);
+        }
+        let greeks = opt.scenario_rate_greeks(&[0.0, 0.01]);
+        assert!(greeks[0].iter().all(|change| *change == Greeks::default()));
+        let mut shifted = opt.clone();
+        for r in shifted.opt_data.rfr.iter_mut() {
+            *r += 0.01;
+        }
+        shifted.get_greeks();
+        for (row, change) in greeks[1].iter().enumerate() {
+            let expected = shifted.greeks[row] - opt.greeks[row];
+            assert!((change.delta - expected.delta).abs() < 1e-12, "row {}", row);
+            assert!((change.rho - expected.rho).abs() < 1e-12, "row {}", row);
+        }
+    }
+
+    #[test]
+    fn rate_repricer() {
+        let mut opt = chain();
+        opt.opt_data.volatility[0] = 0.0;
+        opt.opt_data.strike[1] = 0.0;
+        let models = [
+            BlackScholesModel::new(),
+            BlackScholesModel::new().with_underlying_is_forward(true),
+            BlackScholesModel::new().with_rate_day_count(DayCount::Act365Fixed),
+        ];
+        for model in models {
+            let repricer = model.rate_repricer(&opt);
+            assert_eq!(repricer.len(), 7);
+            for shift in [-0.02, 0.0, 0.0025, 0.05] {
+                let mut shifted = opt.clone();
+                for r in shifted.opt_data.rfr.iter_mut() {
+                    *r += shift;
+                }
+                let full = model.get_price(&shifted);
+                for (a, b) in repricer.prices(shift).iter().zip(&full) {
+                    assert!(
+                        (a - b).abs() < 1e-10,
+                        "{:?} {}: {} vs {}",
+                        model,
+                        shift,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn vega_for_shift() {
+        let mut opt = chain();
+        opt.get_greeks();
+        // Small moves agree with the per vol point vega up to the truncation
+        // of the central difference. With fast-math every price is also off by
+        // up to FAST_CDF_MAX_ERROR times spot plus strike, which dominates the
+        // difference of two close prices.
+        let cdf_error = if cfg!(feature = "fast-math") {
+            FAST_CDF_MAX_ERROR
+        } else {
+            0.0
+        };
+        let points = 0.1;
+        for (row, vega) in opt.vega_for_shift(points).iter().enumerate() {
+            let expected = opt.greeks[row].vega * points;
+            let d = &opt.opt_data;
+            let tol = 1e-4 * expected + cdf_error * (d.underlying[row] + d.strike[row]);
+            assert!((vega - expected).abs() < tol, "row {}", row);
+        }
+        let big = opt.vega_for_shift(5.0);
+        let up = opt.price_with_vols(&[0.40; 7]).unwrap();
+        let down = opt.price_with_vols(&[0.30; 7]).unwrap();
+        assert!((big[6] - (up[6] - down[6]) / 2.0).abs() < 1e-12);
+        assert!(big.iter().all(|v| *v > 0.0));
+
+        // The down leg is clamped at zero vol and the difference rescaled to
+        // the requested move
+        let huge = opt.vega_for_shift(50.0);
+        let up = opt.price_with_vols(&[0.85; 7]).unwrap();
+        let floor = opt.price_with_vols(&[0.0; 7]).unwrap();
+        for row in 0..7 {
+            let expected = (up[row] - floor[row]) * 0.5 / 0.85;
+            assert!((huge[row] - expected).abs() < 1e-12, "row {}", row);
+        }
+    }
+
+    #[test]
+    fn vega_ladder() {
+        let mut opt = chain();
+        opt.opt_data.duration = vec![0.1, 0.1, 0.5, 0.5, 0.5, 2.0, 2.0];
+        opt.get_greeks();
+        let quantities = [1.0, -2.0, 1.0, 1.0, 0.0, 3.0, -1.0];
+        let ladder = opt.vega_ladder(&[0.25, 1.0], &quantities).unwrap();
+        assert_eq!(ladder.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        let g = &opt.greeks;
+        assert!((ladder[&0] - (g[0].vega - 2.0 * g[1].vega)).abs() < 1e-12);
+        assert!((ladder[&2] - (3.0 * g[5].vega - g[6].vega)).abs() < 1e-12);
+        let total: f64 = ladder.values().sum();
+        assert!((total - opt.portfolio_greeks(&quantities).unwrap().vega).abs() < 1e-12);
+        assert!(matches!(
+            opt.vega_ladder(&[0.25, 1.0], &quantities[..5]),
+            Err(OptionsError::Inconsistent(LengthError {
+                name: "quantities",
+                expected: 7,
+                found: 5
+            }))
+        ));
+        assert!(matches!(
+            opt.vega_ladder(&[1.0, 0.25], &quantities),
+            Err(OptionsError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn theta_matches_finite_difference() {
+        let mut opt = chain();
+        opt.get_greeks();
+        // Wide enough that the fast-math cdf error does not dominate
+        let h = 1e-3;
+        for i in 0..7 {
+            let at = |duration| {
+                opt.price_at(
+                    i,
+                    Overrides {
+                        duration: Some(duration),
+                        ..Overrides::default()
+                    },
+                )
+                .unwrap()
+            };
+            let t = opt.opt_data.duration[i];
+            let fd = -(at(t + h) - at(t - h)) / (2.0 * h) / DAYS_PER_YEAR;
+            assert!((opt.greeks[i].theta - fd).abs() < 1e-6, "row {}", i);
+        }
+    }
+
+    #[test]
+    fn pin_risk() {
+        let mut opt = chain();
+        // Strikes 105 to 135 around spot 120, the chain expires in about two months
+        assert!(opt.pin_risk(0.05, 7.0).is_empty());
+        opt.opt_data.duration = vec![2.0 / DAYS_PER_YEAR; 7];
+        assert_eq!(opt.pin_risk(0.01, 7.0), vec![3]);
+        assert_eq!(opt.pin_risk(0.05, 7.0), vec![2, 3, 4]);
+        assert!(opt.pin_risk(0.05, 1.0).is_empty());
+        opt.opt_data.duration[3] = 0.0;
+        assert_eq!(opt.pin_risk(0.05, 1.0), vec![3]);
+    }
+}
+
+#[cfg(test)]
+mod test_units {
+    use crate::units::{Dividend, Rate, Vol};
+
+    #[test]
+    fn validation() {
+        assert_eq!(Vol::new(0.35).unwrap().value(), 0.35);
+        assert!(Vol::new(35.0).is_err());
+        assert!(Vol::new(0.0).is_err());
+        assert_eq!(Rate::new(-0.005).unwrap().value(), -0.005);
+        assert!(Rate::new(3.0).is_err());
+    }
+
+    #[test]
+    fn dividend_to_yield() {
+        let q = Dividend::Yield(Rate::new(0.02).unwrap());
+        assert_eq!(q.to_yield(100.0, 0.5), 0.02);
+        // Forward reduced by exactly the cash amount
+        let q = Dividend::Amount(2.0).to_yield(100.0, 0.5);
+        assert!((100.0 * (-q * 0.5_f64).exp() - 98.0).abs() < 1e-12);
+    }
+}
+
+#[cfg(test)]
+mod test_parse_date {
+    use crate::opt_data::parse_date;
+    use chrono::{TimeZone, Utc};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn formats() {
+        let expected = Utc.with_ymd_and_hms(2022, 11, 18, 15, 0, 0).unwrap();
+        for s in [
+            "2022-11-18T15:00:00Z",
+            "2022-11-18T15:00:00+00:00",
+            "2022-11-18T10:00:00-05:00",
+            "2022-11-18 15:00:00+00:00",
+            "2022-11-18 15:00:00",
+            "2022-11-18t15:00:00",
+            "2022-11-18T15:00:00.000",
+        ] {
+            assert_eq!(parse_date(s), Ok(expected), "{}", s);
+        }
+        assert_eq!(
+            parse_date("2022-11-18"),
+            Ok(Utc.with_ymd_and_hms(2022, 11, 18, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn negative_offsets() {
+        // Expiry at 16:00 New York time, during and outside daylight saving
+        let summer = Utc.with_ymd_and_hms(2024, 6, 21, 20, 0, 0).unwrap();
+        for s in [
+            "2024-06-21T16:00:00-04:00",
+            "2024-06-21 16:00:00-04:00",
+            "2024-06-21T16:00:00-0400",
+            "2024-06-21 16:00:00.000-0400",
+        ] {
+            assert_eq!(parse_date(s), Ok(summer), "{}", s);
+        }
+        let winter = Utc.with_ymd_and_hms(2024, 12, 20, 21, 0, 0).unwrap();
+        assert_eq!(parse_date("2024-12-20T16:00:00-05:00"), Ok(winter));
+    }
+
+    #[test]
+    fn malformed() {
+        for s in [
+            "",
+            "t",
+            " ",
+            "2022",
+            "2022-11-18 1",
+            "+",
+            "2022-13-45",
+            "ä t ö",
+        ] {
+            assert!(parse_date(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn fuzz_no_panic() {
+        // Random strings biased towards date-like characters
+        let alphabet: Vec<char> = "0123456789-:+tTZ .äé".chars().collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..100_000 {
+            let len = rng.gen_range(0..32);
+            let s: String = (0..len)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            let _ = parse_date(&s);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_exercise {
+    use crate::opt_data::{OptData, ParseConfig};
+    use crate::options_struct::{Exercise, OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::crr::{CrrModel, SANITY_TOL};
+    use crate::pricing_models::dispatch::ExerciseDispatchModel;
+    use crate::pricing_models::PricingModel;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn dispatch_by_exercise_column() {
+        let path = std::env::temp_dir().join("options_exercise_column.csv");
+        std::fs::write(
+            &path,
+            "ticker,opt_type,underlying,strike,settle,maturity,rfr,volatility,exercise\n\
+             AAPL,put,100.0,110.0,2022-01-01,2023-01-01,0.05,0.3,american\n\
+             AAPL,put,100.0,110.0,2022-01-01,2023-01-01,0.05,0.3,European\n\
+             AAPL,call,100.0,110.0,2022-01-01,2023-01-01,0.05,0.3,american\n",
+        )
+        .unwrap();
+        let opt_data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(
+            opt_data.exercise,
+            vec![Exercise::American, Exercise::European, Exercise::American]
+        );
+        let mut opt = Options::new(opt_data, Box::new(ExerciseDispatchModel::default()));
+        opt.get_prices();
+        let bs = BlackScholesModel::new().get_price(&opt);
+        // European rows are priced by Black-Scholes
+        assert_eq!(opt.prices[1], bs[1]);
+        // Early exercise premium on the American put
+        assert!(opt.prices[0] > bs[0] + 0.1);
+        // Without dividends an American call is worth the European one
+        assert!((opt.prices[2] - bs[2]).abs() < 0.05);
+        opt.get_greeks();
+        assert!(opt.greeks[0].delta < 0.0 && opt.greeks[2].delta > 0.0);
+    }
+
+    #[test]
+    fn crr_converges_to_black_scholes() {
+        let path = std::env::temp_dir().join("options_crr_european.csv");
+        std::fs::write(
+            &path,
+            "ticker,opt_type,underlying,strike,settle,maturity,rfr,dividend,volatility\n\
+             AAPL,put,100.0,95.0,2022-01-01,2022-07-01,0.03,0.01,0.25\n\
+             AAPL,call,100.0,105.0,2022-01-01,2022-07-01,0.03,0.01,0.25\n",
+        )
+        .unwrap();
+        let opt = Options::from_file_with(
+            &path,
+            Box::new(BlackScholesModel::new()),
+            &ParseConfig::default(),
+        )
+        .unwrap()
+        .0;
+        let crr = CrrModel::new(500).get_price(&opt);
+        let bs = BlackScholesModel::new().get_price(&opt);
+        for (c, b) in crr.iter().zip(bs.iter()) {
+            assert!((c - b).abs() < 0.02, "{} vs {}", c, b);
+        }
+    }
+
+    #[test]
+    fn american_bounds() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2022, 7, 1, 0, 0, 0).unwrap();
+        let mut rows = Vec::new();
+        for opt_type in [OptTypes::Call, OptTypes::Put] {
+            for strike in [60.0, 90.0, 100.0, 110.0, 160.0] {
+                for dividend in [0.0, 0.05] {
+                    for rfr in [-0.01, 0.0, 0.08] {
+                        rows.push((opt_type, strike, dividend, rfr));
+                    }
+                }
+            }
+        }
+        let n = rows.len();
+        let opt_data = OptData::new(
+            vec!["AAPL".to_string(); n],
+            rows.iter().map(|r| r.0).collect(),
+            vec![100.0; n],
+            rows.iter().map(|r| r.1).collect(),
+            vec![settle; n],
+            vec![maturity; n],
+            rows.iter().map(|r| r.2).collect(),
+            rows.iter().map(|r| r.3).collect(),
+            vec![0.3; n],
+        );
+        let european = Options::new(opt_data.clone(), Box::new(BlackScholesModel::new()));
+        let american = Options::new(
+            opt_data.with_exercise(vec![Exercise::American; n]),
+            Box::new(BlackScholesModel::new()),
+        );
+        let model = CrrModel::new(150).with_sanity_checks(true);
+        let eu = model.get_price(&european);
+        let am = model.get_price(&american);
+        for (i, (a, e)) in am.iter().zip(eu.iter()).enumerate() {
+            let intrinsic = match rows[i].0 {
+                OptTypes::Call => (100.0 - rows[i].1).max(0.0),
+                OptTypes::Put => (rows[i].1 - 100.0).max(0.0),
+            };
+            assert!(*a >= *e - SANITY_TOL, "row {}: {} < {}", i, a, e);
+            assert!(
+                *a >= intrinsic - SANITY_TOL,
+                "row {}: {} < {}",
+                i,
+                a,
+                intrinsic
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_utilities {
+    use crate::error::OptionsError;
+    use crate::greeks::Greeks;
+    use crate::opt_data::ParseError;
+    use crate::opt_data::{OptData, ParseConfig};
+    use crate::options_struct::{LengthError, OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::PricingModel;
+    use crate::units::Vol;
+    use crate::utilities::{
+        chunk_opt, price_file, price_stream, price_stream_with_hook, realized_vol, stream_chunks,
+        CsvSink,
+    };
+    use chrono::{TimeZone, Utc};
+
+    fn chain(n: usize) -> Options {
+        let settle = Utc.with_ymd_and_hms(2022, 9, 14, 2, 22, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2022, 11, 18, 15, 0, 0).unwrap();
+        Options::new(
+            OptData::new(
+                vec!["AAPL".to_string(); n],
+                vec![OptTypes::Call; n],
+                vec![120.0; n],
+                (0..n).map(|i| 100.0 + i as f64).collect(),
+                vec![settle; n],
+                vec![maturity; n],
+                vec![0.0; n],
+                vec![0.03; n],
+                vec![0.35; n],
+            ),
+            Box::new(BlackScholesModel::new()),
+        )
+    }
+
+    #[test]
+    fn csv_sink() {
+        let path = std::env::temp_dir().join("options_csv_sink.csv");
+        let mut sink = CsvSink::new(&path).unwrap();
+        for n in [3, 2] {
+            let mut opt = chain(n);
+            opt.get_prices();
+            opt.get_greeks();
+            sink.append(&opt).unwrap();
+        }
+        assert_eq!(sink.rows(), 5);
+        drop(sink);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 6);
+        assert!(contents.starts_with("ticker,opt_type"));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let path = std::env::temp_dir().join("options_csv_round_trip.csv");
+        let mut opt = chain(2);
+        opt.get_prices();
+        opt.get_greeks();
+        opt.write_csv(path.clone()).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(",2022-09-14T02:22:00+00:00,2022-11-18T15:00:00+00:00,"));
+        let read = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        assert_eq!(read.settles, opt.opt_data.settles);
+        assert_eq!(read.maturities, opt.opt_data.maturities);
+        assert_eq!(read.strike, opt.opt_data.strike);
+    }
+
+    #[test]
+    fn typed_records() {
+        let mut opt = chain(3);
+        opt.get_prices();
+        assert!(opt.to_typed_records().is_err());
+        opt.get_greeks();
+        let records = opt.to_typed_records().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].strike, opt.opt_data.strike[1]);
+        assert_eq!(records[1].settle, opt.opt_data.settles[1]);
+        assert_eq!(records[1].price, opt.prices[1]);
+        assert_eq!(records[1].greeks, opt.greeks[1]);
+        let strings: Vec<[String; 18]> = records.iter().map(|r| r.to_strings()).collect();
+        assert_eq!(strings, opt.to_records().unwrap());
+    }
+
+    #[test]
+    fn realized_vol_of_series() {
+        // Alternating returns of +/-1% have a sample sd of 1% times sqrt(n / (n - 1))
+        let mut prices = vec![100.0];
+        for i in 0..10 {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            prices.push(prices[i] * (sign * 0.01f64).exp());
+        }
+        let expected = 0.01 * (10.0f64 / 9.0).sqrt() * 252.0f64.sqrt();
+        assert!((realized_vol(&prices, 252.0) - expected).abs() < 1e-12);
+        assert_eq!(realized_vol(&[100.0, 100.0, 100.0], 252.0), 0.0);
+        assert!(realized_vol(&[100.0, 101.0], 252.0).is_nan());
+        assert!(realized_vol(&[100.0, 0.0, 101.0], 252.0).is_nan());
+    }
+
+    #[test]
+    fn chunk_sizes() {
+        assert!(chunk_opt(chain(0), 0).is_empty());
+        assert!(chunk_opt(chain(0), 10).is_empty());
+        assert_eq!(chunk_opt(chain(3), 0).len(), 3);
+        let chunks = chunk_opt(chain(7), 3);
+        let lens: Vec<usize> = chunks.iter().map(|c| c.opt_data.tickers.len()).collect();
+        assert_eq!(lens, vec![3, 3, 1]);
+        assert_eq!(chunks[2].opt_data.strike, vec![106.0]);
+        assert_eq!(chunk_opt(chain(6), 3).len(), 2);
+    }
+
+    /// Rejects every chain, or prices none of its rows.
+    #[derive(Clone)]
+    struct FailingModel {
+        reject: bool,
+    }
+
+    impl PricingModel for FailingModel {
+        fn get_price(&self, _opts: &Options) -> Vec<f64> {
+            Vec::new()
+        }
+        fn get_greeks(&self, _opts: &Options) -> Vec<Greeks> {
+            Vec::new()
+        }
+        fn validate_inputs(&self, _opts: &Options) -> Result<(), Vec<String>> {
+            if self.reject {
+                Err(vec!["no prices today".to_string()])
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn price_file_never_panics() {
+        let dir = std::env::temp_dir();
+        let good = dir.join("options_price_file_good.csv");
+        std::fs::write(
+            &good,
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n",
+        )
+        .unwrap();
+        let opt = price_file(&good, Box::new(BlackScholesModel::new())).unwrap();
+        assert_eq!((opt.prices.len(), opt.greeks.len()), (1, 1));
+
+        let backwards = dir.join("options_price_file_backwards.csv");
+        std::fs::write(
+            &backwards,
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-11-18,2022-09-14,0.35\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            price_file(&backwards, Box::new(BlackScholesModel::new())),
+            Err(OptionsError::Parse(ParseError::Row { line: 2, .. }))
+        ));
+        match price_file(&good, Box::new(FailingModel { reject: true })) {
+            Err(OptionsError::Pricing(reason)) => assert_eq!(reason, "no prices today"),
+            _ => panic!("expected pricing error"),
+        }
+        assert!(matches!(
+            price_file(&good, Box::new(FailingModel { reject: false })),
+            Err(OptionsError::Inconsistent(LengthError {
+                name: "prices",
+                ..
+            }))
+        ));
+        // Results read before they are computed are errors as well
+        let unpriced = Options::new(opt.opt_data.clone(), Box::new(BlackScholesModel::new()));
+        assert_eq!(
+            unpriced.portfolio_greeks(&[1.0]).unwrap_err(),
+            LengthError {
+                name: "greeks",
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn price_stream_matches_price_file() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("options_price_stream_in.csv");
+        let mut contents =
+            String::from("ticker,opt_type,underlying,strike,settle,maturity,volatility\n");
+        for i in 0..7 {
+            contents.push_str(&format!(
+                "AAPL,{},120.0,{}.0,2022-09-14,2022-11-18,0.35\n",
+                if i % 2 == 0 { "call" } else { "put" },
+                105 + 5 * i
+            ));
+        }
+        std::fs::write(&input, contents).unwrap();
+        let output = dir.join("options_price_stream_out.csv");
+        let rows = price_stream(&input, &output, 3, Box::new(BlackScholesModel::new())).unwrap();
+        assert_eq!(rows, 7);
+
+        let mut timings = Vec::new();
+        let timed = dir.join("options_price_stream_timed.csv");
+        let mut hook = |chunk, rows, _elapsed| timings.push((chunk, rows));
+        price_stream_with_hook(
+            &input,
+            &timed,
+            3,
+            Box::new(BlackScholesModel::new()),
+            &mut hook,
+        )
+        .unwrap();
+        assert_eq!(timings, vec![(0, 3), (1, 3), (2, 1)]);
+        assert_eq!(
+            std::fs::read_to_string(&timed).unwrap(),
+            std::fs::read_to_string(&output).unwrap()
+        );
+
+        let whole = dir.join("options_price_stream_whole.csv");
+        let opt = price_file(&input, Box::new(BlackScholesModel::new())).unwrap();
+        let mut sink = CsvSink::new(&whole).unwrap();
+        sink.append(&opt).unwrap();
+        drop(sink);
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            std::fs::read_to_string(&whole).unwrap()
+        );
+
+        let backwards = dir.join("options_price_stream_backwards.csv");
+        std::fs::write(
+            &backwards,
+            "ticker,opt_type,underlying,strike,settle,maturity,volatility\n\
+             AAPL,call,120.0,110.0,2022-09-14,2022-11-18,0.35\n\
+             AAPL,call,120.0,110.0,2022-11-18,2022-09-14,0.35\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            price_stream(&backwards, &output, 1, Box::new(BlackScholesModel::new())),
+            Err(OptionsError::Parse(ParseError::Row { line: 3, .. }))
+        ));
+    }
+
+    /// Black Scholes, sleeping longer for chunks with lower strikes so that
+    /// later chunks finish first.
+    #[derive(Clone)]
+    struct SlowModel;
+
+    impl PricingModel for SlowModel {
+        fn get_price(&self, opts: &Options) -> Vec<f64> {
+            let strike = opts.opt_data.strike[0];
+            std::thread::sleep(std::time::Duration::from_millis(
+                (200.0 - strike) as u64 / 4,
+            ));
+            BlackScholesModel::new().get_price(opts)
+        }
+        fn get_greeks(&self, opts: &Options) -> Vec<Greeks> {
+            BlackScholesModel::new().get_greeks(opts)
+        }
+    }
+
+    #[test]
+    fn price_stream_keeps_order_with_bounded_chunks() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("options_price_stream_pool_in.csv");
+        let mut contents =
+            String::from("ticker,opt_type,underlying,strike,settle,maturity,volatility\n");
+        for i in 0..24 {
+            contents.push_str(&format!(
+                "AAPL,call,120.0,{}.0,2022-09-14,2022-11-18,0.35\n",
+                100 + 2 * i
+            ));
+        }
+        std::fs::write(&input, contents).unwrap();
+        let output = dir.join("options_price_stream_pool_out.csv");
+        let mut order = Vec::new();
+        let mut hook = |chunk, _rows, _elapsed| order.push(chunk);
+        let stats =
+            stream_chunks(&input, &output, 2, Box::new(SlowModel), 3, Some(&mut hook)).unwrap();
+        assert_eq!(stats.rows, 24);
+        assert!(stats.peak_chunks >= 1 && stats.peak_chunks <= 6);
+        assert_eq!(order, (0..12).collect::<Vec<_>>());
+
+        let whole = dir.join("options_price_stream_pool_whole.csv");
+        let opt = price_file(&input, Box::new(BlackScholesModel::new())).unwrap();
+        let mut sink = CsvSink::new(&whole).unwrap();
+        sink.append(&opt).unwrap();
+        drop(sink);
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            std::fs::read_to_string(&whole).unwrap()
+        );
+    }
+
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn options_error_variants() {
+        let mut opt = chain(2);
+        assert!(matches!(
+            opt.write_csv_to(Vec::new()),
+            Err(OptionsError::Inconsistent(_))
+        ));
+        opt.get_prices();
+        opt.get_greeks();
+        let err = opt.write_csv_to(FailingWriter).unwrap_err();
+        assert!(matches!(err, OptionsError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+        let err: OptionsError = Vol::new(35.0).unwrap_err().into();
+        assert!(matches!(err, OptionsError::Validation(_)));
+    }
+}
+
+#[cfg(test)]
+mod test_fx {
+    use crate::opt_data::{OptData, ParseConfig};
+    use crate::options_struct::Options;
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::crr::CrrModel;
+    use crate::pricing_models::fx::{FxAdjustedModel, FxMode};
+    use crate::pricing_models::PricingModel;
+
+    #[test]
+    fn convert_and_quanto() {
+        let path = std::env::temp_dir().join("options_fx_column.csv");
+        std::fs::write(
+            &path,
+            "ticker,opt_type,underlying,strike,settle,maturity,rfr,volatility,fx\n\
+             SAP,call,100.0,110.0,2022-01-01,2023-01-01,0.03,0.3,1.1\n",
+        )
+        .unwrap();
+        let opt = Options::from_file_with(
+            &path,
+            Box::new(BlackScholesModel::new()),
+            &ParseConfig::default(),
+        )
+        .unwrap()
+        .0;
+        assert_eq!(opt.opt_data.fx, vec![1.1]);
+
+        // Converted underlying of 110 is at the money
+        let converted = FxAdjustedModel::new(Box::new(BlackScholesModel::new()), FxMode::Convert)
+            .get_price(&opt);
+        let mut atm = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        atm.underlying = vec![110.0];
+        let atm = Options::new(atm, Box::new(BlackScholesModel::new()));
+        assert!((converted[0] - BlackScholesModel::new().get_price(&atm)[0]).abs() < 1e-9);
+
+        // Positive correlation lowers the quanto drift and the call price
+        let quanto = |correlation| {
+            FxAdjustedModel::new(
+                Box::new(BlackScholesModel::new()),
+                FxMode::Quanto {
+                    correlation,
+                    fx_volatility: 0.1,
+                },
+            )
+            .get_price(&opt)[0]
+        };
+        let plain = BlackScholesModel::new().get_price(&opt)[0];
+        assert!((quanto(0.0) - 1.1 * plain).abs() < 1e-9);
+        assert!(quanto(0.5) < quanto(0.0));
+    }
+
+    #[test]
+    fn validate_inputs() {
+        let path = std::env::temp_dir().join("options_fx_validate.csv");
+        std::fs::write(
+            &path,
+            "ticker,opt_type,underlying,strike,settle,maturity,rfr,volatility,fx\n\
+             SAP,call,100.0,110.0,2022-01-01,2023-01-01,0.03,0,0\n",
+        )
+        .unwrap();
+        let data = OptData::try_from_file(&path, &ParseConfig::default())
+            .unwrap()
+            .0;
+        let fx = FxAdjustedModel::new(Box::new(CrrModel::default()), FxMode::Convert);
+        let opt = Options::new(data, Box::new(fx));
+        assert_eq!(
+            opt.validate_inputs(),
+            Err(vec![
+                "row 0: fx has to be positive, got 0".to_string(),
+                "row 0: CRR needs a positive volatility, got 0".to_string(),
+            ])
+        );
+        let opt = Options::new(opt.opt_data, Box::new(BlackScholesModel::new()));
+        assert_eq!(opt.validate_inputs(), Ok(()));
+    }
+}
+
+#[cfg(test)]
+mod test_curves {
+    use crate::curves::{df_to_rate, DiscountCurve};
+
+    #[test]
+    fn log_linear_interpolation() {
+        assert!((df_to_rate((-0.03_f64).exp(), 1.0) - 0.03).abs() < 1e-15);
+        let curve =
+            DiscountCurve::new(vec![(2.0, (-0.08_f64).exp()), (1.0, (-0.03_f64).exp())]).unwrap();
+        // Pillars are reproduced
+        assert!((curve.rate(1.0) - 0.03).abs() < 1e-12);
+        assert!((curve.rate(2.0) - 0.04).abs() < 1e-12);
+        // Flat forward of 5% between the pillars
+        let df = curve.discount_factor(1.5);
+        assert!((df - (-0.03_f64 - 0.05 * 0.5).exp()).abs() < 1e-12);
+        // Flat forward from time 0 to the first pillar, flat zero rate after the last
+        assert!((curve.rate(0.5) - 0.03).abs() < 1e-12);
+        assert!((curve.rate(5.0) - 0.04).abs() < 1e-12);
+        assert!(DiscountCurve::new(vec![(1.0, 0.0)]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_arbitrage {
+    use crate::arbitrage::ArbitrageViolation;
+    use crate::opt_data::OptData;
+    use crate::options_struct::{OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use crate::pricing_models::PricingModel;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn calendar_and_butterfly() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let near = Utc.with_ymd_and_hms(2022, 4, 1, 0, 0, 0).unwrap();
+        let far = Utc.with_ymd_and_hms(2022, 7, 1, 0, 0, 0).unwrap();
+        let opt_data = OptData::new(
+            vec!["AAPL".to_string(); 4],
+            vec![OptTypes::Call; 4],
+            vec![100.0; 4],
+            vec![90.0, 100.0, 110.0, 100.0],
+            vec![settle; 4],
+            vec![near, near, near, far],
+            vec![0.0; 4],
+            vec![0.02; 4],
+            vec![0.3, 0.3, 0.3, 0.3],
+        );
+        let opt = Options::new(opt_data, Box::new(BlackScholesModel::new()));
+        let mut market: Vec<Option<f64>> = BlackScholesModel::new()
+            .get_price(&opt)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut clean = opt.clone();
+        clean.opt_data = clean.opt_data.with_market_prices(market.clone());
+        assert!(clean.arbitrage_violations(1e-6).is_empty());
+
+        // Inflated middle strike and a far maturity cheaper than the near one
+        market[1] = market[1].map(|p| p + 2.0);
+        market[3] = Some(market[1].unwrap() - 1.0);
+        let mut bad = opt.clone();
+        bad.opt_data = bad.opt_data.with_market_prices(market);
+        let violations = bad.arbitrage_violations(1e-6);
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[0],
+            ArbitrageViolation::Butterfly {
+                rows: (0, 1, 2),
+                ..
+            }
+        ));
+        assert!(matches!(
+            violations[1],
+            ArbitrageViolation::Calendar { rows: (1, 3), .. }
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_normal {
+    use crate::pricing_models::normal::{
+        fast_cdf, fast_pdf, std_normal, StdNormal, FAST_CDF_MAX_ERROR,
+    };
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    #[test]
+    fn fast_approximation_error() {
+        let n = Normal::new(0.0, 1.0).unwrap();
+        for i in -4000..=4000 {
+            let x = i as f64 / 500.0;
+            assert!(
+                (fast_cdf(x) - n.cdf(x)).abs() < FAST_CDF_MAX_ERROR,
+                "cdf at {}",
+                x
+            );
+            assert!((fast_pdf(x) - n.pdf(x)).abs() < 1e-15, "pdf at {}", x);
+        }
+        assert_eq!(fast_cdf(f64::INFINITY), 1.0);
+        assert_eq!(fast_cdf(f64::NEG_INFINITY), 0.0);
+        assert!(fast_cdf(f64::NAN).is_nan());
+        assert!((StdNormal::new().cdf(0.3) - n.cdf(0.3)).abs() < FAST_CDF_MAX_ERROR);
+    }
+
+    #[test]
+    fn shared_std_normal() {
+        assert!(std::ptr::eq(std_normal(), std_normal()));
+        assert_eq!(std_normal().cdf(0.0), 0.5);
+    }
+}
+
+#[cfg(test)]
+mod test_calendar {
+    use crate::calendar::{DayCount, TradingCalendar};
+    use crate::opt_data::{OptData, DAYS_PER_YEAR};
+    use crate::options_struct::{OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn day_count() {
+        let at = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        let (start, end) = (at(2023, 7, 1), at(2024, 7, 1));
+        // 184 days of 2023 and 182 days of the leap year 2024
+        let act_act = DayCount::ActAct.year_fraction(start, end);
+        assert!((act_act - (184.0 / 365.0 + 182.0 / 366.0)).abs() < 1e-12);
+        assert!((DayCount::ActAct.year_fraction(end, start) + act_act).abs() < 1e-12);
+        assert!((DayCount::Act360.year_fraction(start, end) - 366.0 / 360.0).abs() < 1e-12);
+        assert!((DayCount::Act365Fixed.year_fraction(start, end) - 366.0 / 365.0).abs() < 1e-12);
+        assert!(
+            (DayCount::Continuous.year_fraction(start, end) - 366.0 / DAYS_PER_YEAR).abs() < 1e-12
+        );
+        assert_eq!(DayCount::ActAct.year_fraction(start, start), 0.0);
+    }
+
+    #[test]
+    fn trading_day_theta() {
+        let date = |d| NaiveDate::from_ymd_opt(2022, 11, d).unwrap();
+        // Thanksgiving on Thursday 2022-11-24
+        let calendar = TradingCalendar::new([date(24)]);
+        assert!(!calendar.is_trading_day(date(19)));
+        assert_eq!(calendar.next_trading_day(date(18)), date(21));
+        assert_eq!(calendar.next_trading_day(date(23)), date(25));
+        assert_eq!(calendar.trading_days_between(date(18), date(25)), 4);
+        assert_eq!(calendar.trading_days_between(date(25), date(18)), 0);
+
+        let settle = Utc.with_ymd_and_hms(2022, 11, 18, 16, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2022, 11, 25, 16, 0, 0).unwrap();
+        let mut opt = Options::new(
+            OptData::new(
+                vec!["AAPL".to_string()],
+                vec![OptTypes::Call],
+                vec![150.0],
+                vec![150.0],
+                vec![settle],
+                vec![maturity],
+                vec![0.0],
+                vec![0.03],
+                vec![0.3],
+            ),
+            Box::new(BlackScholesModel::new()),
+        );
+        opt.get_greeks();
+        let theta = opt.trading_day_theta(&calendar).unwrap();
+        // Seven calendar days of decay spread over four sessions
+        assert!((theta[0] - opt.greeks[0].theta * 7.0 / 4.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod test_dividends {
+    use crate::dividends::DividendSchedule;
+    use crate::opt_data::{OptData, ParseError, DAYS_PER_YEAR};
+    use crate::options_struct::{OptTypes, Options, PricingError};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn attach_dividends() {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        let maturity = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let mut opt_data = OptData::new(
+            vec!["AAPL".to_string(), "MSFT".to_string(), "TINY".to_string()],
+            vec![OptTypes::Call; 3],
+            vec![100.0, 100.0, 1.0],
+            vec![100.0; 3],
+            vec![settle; 3],
+            vec![maturity; 3],
+            vec![0.01; 3],
+            vec![0.03; 3],
+            vec![0.2; 3],
+        );
+        let path = std::env::temp_dir().join("options_dividends.csv");
+        std::fs::write(
+            &path,
+            "Ticker,Amount,Ex_Date\n\
+             AAPL,1.0,2022-07-01\n\
+             AAPL,1.0,2021-07-01\n\
+             AAPL,1.0,2023-07-01\n\
+             TINY,2.0,2022-07-01\n",
+        )
+        .unwrap();
+        opt_data.attach_dividends(&path).unwrap();
+
+        // Only the dividend within the life of the option counts
+        let ex_date = Utc.with_ymd_and_hms(2022, 7, 1, 0, 0, 0).unwrap();
+        let t = opt_data.duration[0];
+        let pv =
+            (-0.03 * (ex_date - settle).num_seconds() as f64 / (86_400.0 * DAYS_PER_YEAR)).exp();
+        assert!((100.0 * (-opt_data.dividend[0] * t).exp() - (100.0 - pv)).abs() < 1e-9);
+        // Tickers without a schedule keep their dividend
+        assert_eq!(opt_data.dividend[1], 0.01);
+        assert!(opt_data.dividend[2].is_nan());
+        let mut opt = Options::new(opt_data, Box::new(BlackScholesModel::new()));
+        opt.get_prices();
+        assert!(matches!(
+            opt.failed_rows()[..],
+            [(
+                2,
+                PricingError::InvalidInput {
+                    field: "dividend",
+                    ..
+                }
+            )]
+        ));
+
+        assert_eq!(
+            DividendSchedule::new(vec![]).present_value("AAPL", settle, maturity, 0.03),
+            0.0
+        );
+        std::fs::write(&path, "ticker,ex_date,amount\nAAPL,2022-07-01,one\n").unwrap();
+        assert!(matches!(
+            DividendSchedule::from_file(&path),
+            Err(ParseError::Row { line: 2, .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_properties {
+    use crate::opt_data::OptData;
+    use crate::options_struct::{OptTypes, Options};
+    use crate::pricing_models::black_scholes::BlackScholesModel;
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Random in-domain contracts, every one as a call on even rows and a
+    /// put on the following odd row.
+    fn random_chain(rng: &mut ChaCha8Rng, pairs: usize) -> Options {
+        let settle = Utc.with_ymd_and_hms(2022, 1, 3, 14, 30, 0).unwrap();
+        let (mut underlying, mut strike, mut maturities) = (vec![], vec![], vec![]);
+        let (mut dividend, mut rfr, mut volatility) = (vec![], vec![], vec![]);
+        for _ in 0..pairs {
+            let s = rng.gen_range(1.0..1_000.0);
+            let k = s * rng.gen_range(-1.0f64..1.0).exp();
+            let maturity = settle + TimeDelta::minutes(rng.gen_range(60 * 24..5 * 365 * 60 * 24));
+            let (q, r, v) = (
+                rng.gen_range(0.0..0.1),
+                rng.gen_range(-0.02..0.1),
+                rng.gen_range(0.05..1.5),
+            );
+            for _ in 0..2 {
+                underlying.push(s);
+                strike.push(k);
+                maturities.push(maturity);
+                dividend.push(q);
+                rfr.push(r);
+                volatility.push(v);
+            }
+        }
+        let n = 2 * pairs;
+        Options::new(
+            OptData::new(
+                vec!["RAND".to_string(); n],
+                (0..n)
+                    .map(|i| {
+                        if i % 2 == 0 {
+                            OptTypes::Call
+                        } else {
+                            OptTypes::Put
+                        }
+                    })
+                    .collect(),
+                underlying,
+                strike,
+                vec![settle; n],
+                maturities,
+                dividend,
+                rfr,
+                volatility,
+            ),
+            Box::new(BlackScholesModel::new()),
+        )
+    }
+
+    #[test]
+    fn finite_and_parity() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..20 {
+            let mut opt = random_chain(&mut rng, 250);
+            opt.get_prices();
+            opt.get_greeks();
+            assert!(opt.failed_rows().is_empty());
+            let d = &opt.opt_data;
+            for i in (0..d.tickers.len()).step_by(2) {
+                let (call, put) = (opt.prices[i], opt.prices[i + 1]);
+                assert!(call.is_finite() && put.is_finite(), "row {}", i);
+                for g in &opt.greeks[i..i + 2] {
+                    assert!(
+                        [g.delta, g.gamma, g.vega, g.theta, g.rho]
+                            .iter()
+                            .all(|x| x.is_finite()),
+                        "row {}",
+                        i
+                    );
+                }
+                // Put-call parity in prices and deltas
+                let t = d.duration[i];
+                let carry = (-d.dividend[i] * t).exp();
+                let forward = d.underlying[i] * carry - d.strike[i] * (-d.rfr[i] * t).exp();
+                let scale = d.underlying[i] + d.strike[i];
+                assert!((call - put - forward).abs() < 1e-6 * scale, "row {}", i);
+                let delta = opt.greeks[i].delta - opt.greeks[i + 1].delta;
+                assert!((delta - carry).abs() < 1e-6, "row {}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_inputs_no_panic() {
+        let special = [
+            0.0,
+            -1.0,
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            1e300,
+            1e-300,
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for _ in 0..20 {
+            let mut opt = random_chain(&mut rng, 50);
+            let d = &mut opt.opt_data;
+            for column in [
+                &mut d.underlying,
+                &mut d.strike,
+                &mut d.duration,
+                &mut d.dividend,
+                &mut d.rfr,
+                &mut d.volatility,
+            ] {
+                let i = rng.gen_range(0..column.len());
+                column[i] = special[rng.gen_range(0..special.len())];
+            }
+            opt.get_prices();
+            opt.get_greeks();
+            assert_eq!(opt.prices.len(), 100);
+            // Rows reported as failed are the only ones allowed to be non finite
+            for (i, price) in opt.prices.iter().enumerate() {
+                assert!(price.is_finite() || opt.errors[i].is_some(), "row {}", i);
+            }
+        }
     }
 }
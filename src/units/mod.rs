@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+
+/// # UnitError
+/// Returned when a value is outside the plausible range of its unit. Usually
+/// the value was given in percent rather than as a decimal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitError {
+    /// Name of the unit, e.g. "volatility".
+    pub unit: &'static str,
+    /// The rejected value.
+    pub value: f64,
+    /// Exclusive bounds of the accepted range.
+    pub range: (f64, f64),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} outside ({}, {}), expected a decimal (0.35 rather than 35)",
+            self.unit, self.value, self.range.0, self.range.1
+        )
+    }
+}
+
+impl Error for UnitError {}
+
+/// # check_range
+/// Checks that `value` lies strictly within `range`.
+fn check_range(unit: &'static str, value: f64, range: (f64, f64)) -> Result<f64, UnitError> {
+    if value > range.0 && value < range.1 {
+        Ok(value)
+    } else {
+        Err(UnitError { unit, value, range })
+    }
+}
+
+/// # Vol
+/// Annualized volatility as a decimal, e.g. `Vol::new(0.35)` for 35%.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Vol(f64);
+
+impl Vol {
+    /// Accepted range of volatilities, exclusive.
+    pub const RANGE: (f64, f64) = (0.0, 10.0);
+
+    /// # Vol::new
+    /// Validating constructor for Vol
+    ///
+    /// # args:
+    /// * `value` - Annualized volatility as a decimal.
+    ///
+    /// # returns:
+    /// A `Vol` or a [`UnitError`] if `value` is outside [`Vol::RANGE`].
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        check_range("volatility", value, Vol::RANGE).map(Vol)
+    }
+
+    /// # self.value
+    /// Returns the raw decimal value.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// # Rate
+/// Annualized continuously compounded rate as a decimal, e.g. `Rate::new(0.03)` for 3%.
+/// Used for both risk free rates and dividend yields.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Rate(f64);
+
+impl Rate {
+    /// Accepted range of rates, exclusive.
+    pub const RANGE: (f64, f64) = (-1.0, 1.0);
+
+    /// # Rate::new
+    /// Validating constructor for Rate
+    ///
+    /// # args:
+    /// * `value` - Annualized rate as a decimal.
+    ///
+    /// # returns:
+    /// A `Rate` or a [`UnitError`] if `value` is outside [`Rate::RANGE`].
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        check_range("rate", value, Rate::RANGE).map(Rate)
+    }
+
+    /// # self.value
+    /// Returns the raw decimal value.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// # Dividend
+/// Explicit dividend input, distinguishing a continuous yield from a cash
+/// amount. Pricing models use continuous yields, amounts are converted with
+/// [`Dividend::to_yield`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dividend {
+    /// # Dividend::Yield
+    /// Continuous dividend yield, e.g. `Rate::new(0.02)` for 2% per year.
+    Yield(Rate),
+    /// # Dividend::Amount
+    /// Total cash dividend per share paid over the life of the option.
+    Amount(f64),
+}
+
+impl Dividend {
+    /// # self.to_yield
+    /// Converts the dividend to the continuous yield the pricing models expect.
+    /// A cash amount `D` is converted to the yield that reduces the forward by
+    /// the same amount, `q = -ln(1 - D / S) / T`, ignoring discounting of the
+    /// dividend itself.
+    ///
+    /// # args:
+    /// * `underlying` - Underlying price.
+    /// * `duration` - Duration of the option in years.
+    ///
+    /// # returns:
+    /// The continuous dividend yield, 0.0 for amounts on expired options.
+    pub fn to_yield(&self, underlying: f64, duration: f64) -> f64 {
+        match self {
+            Dividend::Yield(rate) => rate.value(),
+            Dividend::Amount(amount) => {
+                if duration <= 0.0 {
+                    0.0
+                } else {
+                    -(1.0 - amount / underlying).ln() / duration
+                }
+            }
+        }
+    }
+}
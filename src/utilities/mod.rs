@@ -1,62 +1,41 @@
-use crate::opt_data::OptData;
-use crate::options_struct::Options;
+use crate::error::OptionsError;
+use crate::opt_data::{try_parse_input, ChunkReader, ParseConfig};
+use crate::options_struct::{Options, RECORD_HEADERS};
 use crate::pricing_models::black_scholes::BlackScholesModel;
+use crate::pricing_models::PricingModel;
+use csv::Writer;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// # chunk_opts
 /// Chunk a single large [`Options`] into chunks for parallel computation.
 ///
 /// # args:
 /// * `opt` - Input option to chunk
-/// * `size` - Size of chunks (1000 is a good starting point)
+/// * `size` - Size of chunks (1000 is a good starting point), clamped to at least 1.
 ///
 /// # returns:
 /// A Vec of [`Options`]. Maximum size of each `Options` set by size argument.
+/// Empty input gives no chunks.
 pub fn chunk_opt(opt: Options, size: usize) -> Vec<Options> {
+    let size = size.max(1);
     let n_options = opt.opt_data.tickers.len(); // Number of options
-    let chunks = (n_options as f64 / size as f64) as usize; // Number of chunks
-    let remaining = n_options % size;
-    let mut chunk_vec = Vec::with_capacity(chunks);
-    let mut idx= 0;
-    for i in 0..=(chunks - 1) {
-        idx = i * size; // Starting index of next chunk
-        // If there are full chunks left to allocate
-        chunk_vec.push(Options::new(
-            OptData::new(
-                opt.opt_data.tickers[idx..idx + size].to_vec(),
-                opt.opt_data.opt_types[idx..idx + size].to_vec(),
-                opt.opt_data.underlying[idx..idx + size].to_vec(),
-                opt.opt_data.strike[idx..idx + size].to_vec(),
-                opt.opt_data.settles[idx..idx + size].to_vec(),
-                opt.opt_data.maturities[idx..idx + size].to_vec(),
-                opt.opt_data.dividend[idx..idx + size].to_vec(),
-                opt.opt_data.rfr[idx..idx + size].to_vec(),
-                opt.opt_data.volatility[idx..idx + size].to_vec(),
-            ),
-            Box::new(BlackScholesModel::new()),
-        ));
-    };
-    if remaining != 0 {
-        // If not enough for full chunk allocate the rest
-        chunk_vec.push(Options::new(
-            OptData::new(
-                opt.opt_data.tickers[idx..n_options].to_vec(),
-                opt.opt_data.opt_types[idx..n_options].to_vec(),
-                opt.opt_data.underlying[idx..n_options].to_vec(),
-                opt.opt_data.strike[idx..n_options].to_vec(),
-                opt.opt_data.settles[idx..n_options].to_vec(),
-                opt.opt_data.maturities[idx..n_options].to_vec(),
-                opt.opt_data.dividend[idx..n_options].to_vec(),
-                opt.opt_data.rfr[idx..n_options].to_vec(),
-                opt.opt_data.volatility[idx..n_options].to_vec(),
-            ),
-            Box::new(BlackScholesModel::new()),
-        ))
-    }
-
-    chunk_vec
+    (0..n_options)
+        .step_by(size)
+        .map(|start| {
+            // The last chunk holds the remainder
+            Options::new(
+                opt.opt_data.slice(start, (start + size).min(n_options)),
+                Box::new(BlackScholesModel::new()),
+            )
+        })
+        .collect()
 }
 
 /// # collect_chunks
@@ -80,12 +59,225 @@ pub fn collect_chunks(opts: Vec<Options>) -> Options {
         ret_opt.opt_data.dividend.extend(opt.opt_data.dividend);
         ret_opt.opt_data.rfr.extend(opt.opt_data.rfr);
         ret_opt.opt_data.volatility.extend(opt.opt_data.volatility);
+        ret_opt.opt_data.multiplier.extend(opt.opt_data.multiplier);
+        ret_opt.opt_data.currency.extend(opt.opt_data.currency);
+        ret_opt
+            .opt_data
+            .market_price
+            .extend(opt.opt_data.market_price);
+        ret_opt.opt_data.bid.extend(opt.opt_data.bid);
+        ret_opt.opt_data.ask.extend(opt.opt_data.ask);
+        ret_opt.opt_data.exercise.extend(opt.opt_data.exercise);
+        ret_opt.opt_data.fx.extend(opt.opt_data.fx);
+        ret_opt.opt_data.quoted_iv.extend(opt.opt_data.quoted_iv);
+        ret_opt.opt_data.source.extend(opt.opt_data.source);
         ret_opt.prices.extend(opt.prices);
         ret_opt.greeks.extend(opt.greeks);
+        ret_opt.implied_vol.extend(opt.implied_vol);
+        ret_opt.errors.extend(opt.errors);
     }
     ret_opt
 }
 
+/// # price_file
+/// Parses a file and computes prices and greeks without panicking on bad data.
+/// Parsing is strict, see [`try_parse_input`], and pricing goes through
+/// [`Options::try_price`].
+///
+/// # args:
+/// * `path` - Path to the input file.
+/// * `model` - Pricing model used to compute prices and greeks.
+///
+/// # returns:
+/// The priced [`Options`], or the first error encountered.
+pub fn price_file(
+    path: &PathBuf,
+    model: Box<dyn PricingModel + Send>,
+) -> Result<Options, OptionsError> {
+    let (opt_data, _) = try_parse_input(path, &ParseConfig::default())?;
+    let mut opt = Options::new(opt_data, model);
+    opt.try_price()?;
+    Ok(opt)
+}
+
+/// # price_stream
+/// Prices a file chunk by chunk with bounded memory: parse, price and write
+/// each chunk, then release it before the next. A reader thread parses chunks
+/// for a pool of workers, one per available core, and results are written in
+/// input order. At most two chunks per worker are parsed and not yet written,
+/// so memory is bounded regardless of file size. Parsing is strict, see
+/// [`ChunkReader`].
+///
+/// # args:
+/// * `input` - Path of the file to price.
+/// * `output` - Path of the csv file to write, same format as [`CsvSink`].
+/// * `chunk_size` - Rows per chunk, clamped to at least 1.
+/// * `model` - Pricing model, cloned for every chunk.
+///
+/// # returns:
+/// The number of rows written, or the first parse, pricing or io error in
+/// input order. Chunks before the failing one are written.
+pub fn price_stream(
+    input: &PathBuf,
+    output: &PathBuf,
+    chunk_size: usize,
+    model: Box<dyn PricingModel + Send>,
+) -> Result<usize, OptionsError> {
+    stream_chunks(input, output, chunk_size, model, default_workers(), None).map(|s| s.rows)
+}
+
+/// # price_stream_with_hook
+/// Like [`price_stream`], calling `hook` after every chunk with the chunk
+/// index, its number of rows and the time spent computing its prices and
+/// greeks. Parsing and writing are not included, the hook sees model cost
+/// only, e.g. to tune chunk sizes or spot slow inputs. The hook is called
+/// in input order as chunks are written.
+///
+/// # args:
+/// * `input` - Path of the file to price.
+/// * `output` - Path of the csv file to write, same format as [`CsvSink`].
+/// * `chunk_size` - Rows per chunk, clamped to at least 1.
+/// * `model` - Pricing model, cloned for every chunk.
+/// * `hook` - Called as `hook(chunk_index, rows, elapsed)`, chunks counted from 0.
+///
+/// # returns:
+/// The number of rows written, or the first parse, pricing or io error.
+pub fn price_stream_with_hook(
+    input: &PathBuf,
+    output: &PathBuf,
+    chunk_size: usize,
+    model: Box<dyn PricingModel + Send>,
+    hook: &mut dyn FnMut(usize, usize, Duration),
+) -> Result<usize, OptionsError> {
+    stream_chunks(
+        input,
+        output,
+        chunk_size,
+        model,
+        default_workers(),
+        Some(hook),
+    )
+    .map(|s| s.rows)
+}
+
+/// # default_workers
+/// Number of pricing workers of [`price_stream`], one per available core.
+fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, usize::from)
+}
+
+/// # StreamStats
+/// Outcome of [`stream_chunks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct StreamStats {
+    /// Rows written.
+    pub rows: usize,
+    /// Largest number of chunks parsed and not yet written at any time.
+    pub peak_chunks: usize,
+}
+
+/// # stream_chunks
+/// Driver of [`price_stream`]. A chunk is only parsed once a permit is free,
+/// there are `2 * workers` permits and one is returned after every write, so
+/// at most that many chunks are held at once, whether queued, being priced or
+/// waiting for an earlier chunk to be written. The clock is only read when a
+/// hook is given. A failed chunk or write stops the reader before it takes
+/// the next permit, so no further chunks are parsed.
+///
+/// # args:
+/// * `workers` - Number of pricing threads, clamped to at least 1.
+pub(crate) fn stream_chunks(
+    input: &PathBuf,
+    output: &PathBuf,
+    chunk_size: usize,
+    model: Box<dyn PricingModel + Send>,
+    workers: usize,
+    mut hook: Option<&mut dyn FnMut(usize, usize, Duration)>,
+) -> Result<StreamStats, OptionsError> {
+    let workers = workers.max(1);
+    let max_chunks = 2 * workers;
+    let mut reader = ChunkReader::new(input, chunk_size, &ParseConfig::default())?;
+    let mut sink = CsvSink::new(output)?;
+    let timed = hook.is_some();
+    let held = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let model = &model;
+    let (job_tx, job_rx) = mpsc::sync_channel(max_chunks);
+    let job_rx = Mutex::new(job_rx.into_iter().enumerate());
+    thread::scope(|s| {
+        let (permit_tx, permit_rx) = mpsc::sync_channel(max_chunks);
+        for _ in 0..max_chunks {
+            permit_tx
+                .send(())
+                .expect("Permit channel holds one slot per permit.");
+        }
+        let (result_tx, result_rx) = mpsc::sync_channel(max_chunks);
+        let (held, peak, failed) = (&held, &peak, &failed);
+
+        s.spawn(move || {
+            // Returns once the writer is gone, e.g. after an error
+            while permit_rx.recv().is_ok() && !failed.load(Ordering::SeqCst) {
+                let Some(chunk) = reader.next() else {
+                    break;
+                };
+                let now = held.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                if job_tx.send(chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..workers {
+            let (job_rx, result_tx) = (&job_rx, result_tx.clone());
+            s.spawn(move || loop {
+                let job = job_rx.lock().expect("Job queue poisoned.").next();
+                let Some((index, chunk)) = job else {
+                    break;
+                };
+                let priced = chunk.map_err(OptionsError::from).and_then(|(opt_data, _)| {
+                    let mut opt = Options::new(opt_data, model.clone_box());
+                    let start = timed.then(Instant::now);
+                    opt.try_price()?;
+                    Ok((opt, start.map(|t| t.elapsed())))
+                });
+                if priced.is_err() {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                if result_tx.send((index, priced)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_tx);
+
+        // Results arrive in any order, hold them until their turn
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, priced) in result_rx {
+            pending.insert(index, priced);
+            while let Some(priced) = pending.remove(&next) {
+                let (opt, elapsed) = priced?;
+                if let (Some(hook), Some(elapsed)) = (hook.as_mut(), elapsed) {
+                    hook(next, opt.opt_data.tickers.len(), elapsed);
+                }
+                sink.append(&opt)
+                    .inspect_err(|_| failed.store(true, Ordering::SeqCst))?;
+                drop(opt);
+                held.fetch_sub(1, Ordering::SeqCst);
+                next += 1;
+                // The reader is gone once the input is exhausted
+                let _ = permit_tx.send(());
+            }
+        }
+        Ok(StreamStats {
+            rows: sink.rows(),
+            peak_chunks: peak.load(Ordering::SeqCst),
+        })
+    })
+}
+
 /// # retry_open_file
 /// Retries opening a file until successful.
 ///
@@ -94,11 +286,119 @@ pub fn collect_chunks(opts: Vec<Options>) -> Options {
 pub fn retry_open_file(path: &PathBuf) -> Option<BufReader<File>> {
     let start = std::time::Instant::now();
     loop {
-        if let Ok(f) = File::open(&path) {
-            return Some(BufReader::new(f))
+        if let Ok(f) = File::open(path) {
+            return Some(BufReader::new(f));
         }
         if start.elapsed() > std::time::Duration::from_secs(5) {
-            return None
+            return None;
         }
     }
 }
+
+/// # realized_vol
+/// Annualized realized volatility of a price series, the sample standard
+/// deviation of the log returns scaled by `sqrt(periods_per_year)`. Usable
+/// as [`OptData::volatility`](crate::opt_data::OptData::volatility) to
+/// price at historical vol.
+///
+/// # args:
+/// * `prices` - Prices in time order, equally spaced.
+/// * `periods_per_year` - Observations per year, e.g. 252 for daily closes.
+///
+/// # returns:
+/// The annualized volatility, NaN for fewer than three prices or if any
+/// price is not positive.
+pub fn realized_vol(prices: &[f64], periods_per_year: f64) -> f64 {
+    if prices.len() < 3 {
+        return f64::NAN;
+    }
+    let returns: Vec<f64> = prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (variance * periods_per_year).sqrt()
+}
+
+/// # bisect
+/// Finds a root of `f` in `[lo, hi]` by bisection.
+///
+/// # args:
+/// * `f` - Function to find the root of, has to change sign over the bracket.
+/// * `lo` - Lower end of the bracket.
+/// * `hi` - Upper end of the bracket.
+/// * `tol` - Width of the bracket at which to stop.
+///
+/// # returns:
+/// The root, or `None` if `f` does not change sign over the bracket.
+pub fn bisect<F: Fn(f64) -> f64>(f: F, mut lo: f64, mut hi: f64, tol: f64) -> Option<f64> {
+    let mut f_lo = f(lo);
+    let f_hi = f(hi);
+    if f_lo == 0.0 {
+        return Some(lo);
+    }
+    if f_hi == 0.0 {
+        return Some(hi);
+    }
+    if f_lo.signum() == f_hi.signum() || f_lo.is_nan() || f_hi.is_nan() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        let f_mid = f(mid);
+        if f_mid == 0.0 || hi - lo < tol {
+            return Some(mid);
+        }
+        if f_mid.signum() == f_lo.signum() {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+/// # CsvSink
+/// Writes priced [`Options`] to a single csv file chunk by chunk, so chunks
+/// can be released as soon as they are written. Same format as
+/// [`Options::write_csv`].
+pub struct CsvSink {
+    wtr: Writer<File>,
+    rows: usize,
+}
+
+impl CsvSink {
+    /// # CsvSink::new
+    /// Creates the file and writes the header.
+    ///
+    /// # args:
+    /// * `path` - Path of the output file, truncated if it exists.
+    ///
+    /// # returns:
+    /// A `CsvSink` ready to append to.
+    pub fn new(path: &PathBuf) -> Result<Self, OptionsError> {
+        let mut wtr = Writer::from_path(path)?;
+        wtr.write_record(RECORD_HEADERS)?;
+        Ok(CsvSink { wtr, rows: 0 })
+    }
+
+    /// # self.append
+    /// Writes the rows of a priced chunk.
+    ///
+    /// # args:
+    /// * `opt` - Chunk with prices and greeks computed.
+    pub fn append(&mut self, opt: &Options) -> Result<(), OptionsError> {
+        for rec in opt.to_records()? {
+            self.wtr.write_record(rec)?;
+        }
+        self.wtr.flush()?;
+        self.rows += opt.opt_data.tickers.len();
+        Ok(())
+    }
+
+    /// # self.rows
+    /// Number of rows written so far, excluding the header.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+}